@@ -1,14 +1,25 @@
 //! Error module.
-use std::fmt::Display;
+use std::{
+    fmt::Display,
+    path::{Path, PathBuf},
+};
 
 #[derive(Debug)]
 pub enum Error {
     IOError(std::io::Error),
+    /// An IO error that occurred while accessing a specific file.
+    FileError {
+        path: PathBuf,
+        source: std::io::Error,
+    },
     IESError(crate::io::ies::Error),
     LDTError(crate::io::eulumdat::Error),
     InvalidFileType(String),
     BuildError(Box<Error>),
-    OperationError(Box<crate::ops::err::Error>)
+    OperationError(Box<crate::ops::err::Error>),
+    /// An error encoding an image, such as when exporting a heatmap.
+    #[cfg(feature = "image")]
+    ImageError(image::ImageError),
 }
 
 impl From<std::io::Error> for Error {
@@ -17,6 +28,32 @@ impl From<std::io::Error> for Error {
     }
 }
 
+impl Error {
+    /// Wraps an IO error with the path of the file that was being accessed.
+    pub fn file_error(path: &Path, source: std::io::Error) -> Self {
+        Error::FileError {
+            path: path.to_path_buf(),
+            source,
+        }
+    }
+
+    /// Wraps an error that occurred while building a photometric web, such as a reader failing
+    /// to parse its input file.
+    pub fn build_error(err: Error) -> Self {
+        Error::BuildError(Box::new(err))
+    }
+
+    /// Returns the line number of the file that a parse error occurred on, if known.
+    pub fn line(&self) -> Option<usize> {
+        match self {
+            Error::IESError(ref e) => e.line(),
+            Error::LDTError(ref e) => e.line(),
+            Error::BuildError(ref e) => e.line(),
+            _ => None,
+        }
+    }
+}
+
 impl From<crate::io::ies::Error> for Error {
     fn from(err: crate::io::ies::Error) -> Self {
         Error::IESError(err)
@@ -29,6 +66,13 @@ impl From<crate::io::eulumdat::Error> for Error {
     }
 }
 
+#[cfg(feature = "image")]
+impl From<image::ImageError> for Error {
+    fn from(err: image::ImageError) -> Self {
+        Error::ImageError(err)
+    }
+}
+
 impl From<crate::ops::err::Error> for Error {
     fn from(err: crate::ops::err::Error) -> Self {
         Error::OperationError(Box::new(err))
@@ -40,12 +84,83 @@ impl Display for Error {
         write!(f, "{}", {
             match self {
                 Error::IOError(ref e) => format!("IO Error: {}", e),
+                Error::FileError { ref path, ref source } => {
+                    format!("IO Error ({}): {}", path.display(), source)
+                }
                 Error::IESError(ref e) => format!("IES Parse Error: {}", e),
                 Error::LDTError(ref e) => format!("EULUMDAT (LDT) Parse Error: {}", e),
                 Error::BuildError(ref err) => format!("Photometric Web Build Error: {}", err),
                 Error::InvalidFileType(ref ext) => format!("Invalid file type: {}", ext),
                 Error::OperationError(ref e) => format!("Operation Error: {}", e),
+                #[cfg(feature = "image")]
+                Error::ImageError(ref e) => format!("Image Error: {}", e),
             }
         })
     }
 }
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::IOError(ref e) => Some(e),
+            Error::FileError { ref source, .. } => Some(source),
+            Error::IESError(ref e) => Some(e),
+            Error::LDTError(ref e) => Some(e),
+            Error::BuildError(ref e) => Some(e.as_ref()),
+            Error::OperationError(ref e) => Some(e.as_ref()),
+            Error::InvalidFileType(_) => None,
+            #[cfg(feature = "image")]
+            Error::ImageError(ref e) => Some(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Error;
+    use std::{error::Error as StdError, path::Path};
+
+    /// Check that a build failure caused by a missing file reads through the whole chain.
+    #[test]
+    fn test_nested_error_chain() {
+        let io_err = std::io::Error::new(std::io::ErrorKind::NotFound, "no such file");
+        let err = Error::build_error(Error::file_error(Path::new("lamp.ies"), io_err));
+        assert_eq!(
+            err.to_string(),
+            "Photometric Web Build Error: IO Error (lamp.ies): no such file"
+        );
+
+        let file_err = err.source().unwrap();
+        assert_eq!(file_err.to_string(), "IO Error (lamp.ies): no such file");
+        assert_eq!(file_err.source().unwrap().to_string(), "no such file");
+        assert!(file_err.source().unwrap().source().is_none());
+    }
+
+    /// Check that every error type in the crate can be used as a boxed standard error,
+    /// and that wrapped errors are exposed as the source.
+    #[test]
+    fn test_errors_coerce_to_std_error() {
+        let parse_err = "x".parse::<f64>().unwrap_err();
+        let errors: Vec<Box<dyn StdError>> = vec![
+            Box::new(Error::InvalidFileType("txt".to_string())),
+            Box::new(crate::io::ies::Error::ParseFloatError(
+                3,
+                None,
+                parse_err.clone(),
+            )),
+            Box::new(crate::io::eulumdat::Error::ParseFloatError(3, parse_err)),
+            Box::new(crate::ops::err::Error::NoPlanes),
+        ];
+        assert!(errors.iter().all(|err| !err.to_string().is_empty()));
+        assert!(errors[1].source().is_some());
+        assert!(errors[2].source().is_some());
+
+        let err: Error = crate::ops::err::Error::NoPlanes.into();
+        assert_eq!(
+            err.source().unwrap().to_string(),
+            crate::ops::err::Error::NoPlanes.to_string()
+        );
+        let boxed: Box<dyn StdError> = err.into();
+        assert!(boxed.to_string().starts_with("Operation Error"));
+    }
+}
@@ -0,0 +1,84 @@
+use property::Property;
+
+/// A single non-fatal issue found while parsing a file.
+#[derive(Debug, Clone, PartialEq, Property)]
+#[property(get(public), set(disable))]
+pub struct Diagnostic {
+    /// The (1-indexed) line of the file the issue was found on, if it relates to a specific line.
+    line: Option<usize>,
+    /// A description of the issue.
+    message: String,
+}
+
+impl Diagnostic {
+    /// Returns a new diagnostic for the given line and message.
+    pub fn new(line: Option<usize>, message: &str) -> Self {
+        Self {
+            line,
+            message: message.to_owned(),
+        }
+    }
+}
+
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "Line {}: {}", line, self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+/// A collector for the non-fatal issues found while parsing, so that they can be
+/// reported to the user without failing the parse.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Diagnostics {
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Diagnostics {
+    /// Returns a new, empty, diagnostics collector.
+    pub fn new() -> Self {
+        Self {
+            ..Default::default()
+        }
+    }
+
+    /// Records a new diagnostic.
+    pub fn push(&mut self, line: Option<usize>, message: &str) {
+        self.diagnostics.push(Diagnostic::new(line, message));
+    }
+
+    /// Appends all of the diagnostics from another collector to this one.
+    pub fn extend(&mut self, other: &Diagnostics) {
+        self.diagnostics.extend(other.diagnostics.iter().cloned());
+    }
+
+    /// Removes all of the recorded diagnostics.
+    pub fn clear(&mut self) {
+        self.diagnostics.clear();
+    }
+
+    /// Returns the number of recorded diagnostics.
+    pub fn len(&self) -> usize {
+        self.diagnostics.len()
+    }
+
+    /// Returns true if no diagnostics have been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.diagnostics.is_empty()
+    }
+
+    /// Returns an iterator over the recorded diagnostics.
+    pub fn iter(&self) -> std::slice::Iter<'_, Diagnostic> {
+        self.diagnostics.iter()
+    }
+}
+
+impl std::ops::Index<usize> for Diagnostics {
+    type Output = Diagnostic;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.diagnostics[index]
+    }
+}
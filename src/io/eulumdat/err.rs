@@ -1,19 +1,45 @@
+use super::EulumdatSymmetry;
 use std::{fmt::Display, rc::Rc};
 
 #[derive(Debug, Clone)]
 pub enum Error {
+    EmptyFile,
     TooManyLines(usize),
     ParseFloatError(usize, std::num::ParseFloatError),
     ParseIntError(usize, std::num::ParseIntError),
     InvalidUnit(usize),
     ArrayTooShort(usize, usize, usize),
     FromPrimitiveError(usize, Rc<dyn std::error::Error>),
+    IncompleteCPlanes(usize, usize),
+    InvalidSymmetryPlanes(usize, usize),
+    SymmetryAlreadyApplied(EulumdatSymmetry),
+    NoGammaAngles,
+}
+
+impl Error {
+    /// Returns the line number that the error occurred on, if the error relates to a single line.
+    pub fn line(&self) -> Option<usize> {
+        match self {
+            Error::TooManyLines(iline)
+            | Error::ParseFloatError(iline, _)
+            | Error::ParseIntError(iline, _)
+            | Error::InvalidUnit(iline)
+            | Error::ArrayTooShort(iline, _, _)
+            | Error::FromPrimitiveError(iline, _) => Some(*iline),
+            Error::EmptyFile
+            | Error::IncompleteCPlanes(_, _)
+            | Error::InvalidSymmetryPlanes(_, _)
+            | Error::SymmetryAlreadyApplied(_)
+            | Error::NoGammaAngles => None,
+        }
+    }
 }
 
 impl Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", {
             match self {
+                Error::EmptyFile => "The file contains no lines. ".to_string(),
                 Error::TooManyLines(ref iline) => {
                     format!("Line {}: The file contains too many lines", iline)
                 }
@@ -38,7 +64,40 @@ impl Display for Error {
                 Error::FromPrimitiveError(ref iline, ref err) => {
                     format!("Error converting from primitive on line {}: {}", iline, err)
                 }
+                Error::IncompleteCPlanes(ref expected, ref found) => {
+                    format!(
+                        "Expected intensities for all {} C-planes, but found {}. ",
+                        expected, found
+                    )
+                }
+                Error::InvalidSymmetryPlanes(ref expected, ref found) => {
+                    format!(
+                        "Expected {} C-planes in the range of the symmetry, but found {}. ",
+                        expected, found
+                    )
+                }
+                Error::SymmetryAlreadyApplied(ref symmetry) => {
+                    format!(
+                        "The intensities are already stored with {:?} symmetry, so cannot be trimmed again. ",
+                        symmetry
+                    )
+                }
+                Error::NoGammaAngles => {
+                    "The file has no gamma angles to divide the intensities into C-planes. "
+                        .to_string()
+                }
             }
         })
     }
 }
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::ParseFloatError(_, ref err) => Some(err),
+            Error::ParseIntError(_, ref err) => Some(err),
+            Error::FromPrimitiveError(_, ref err) => Some(err.as_ref()),
+            _ => None,
+        }
+    }
+}
@@ -1,16 +1,21 @@
 use super::err as ldt_err;
-use super::{util, EulumdatSymmetry, EulumdatType};
+use super::{util, EulumdatSymmetry, EulumdatType, LampSet};
 use crate::photweb::{
     mirror_first_hemisphere, mirror_first_quadrant, IntensityUnits, PhotometricWeb, Plane,
     PlaneOrientation, mirror_second_and_third_quadrants,
 };
-use crate::util::geom::degrees_to_radians;
-use crate::{err::Error, photweb::PhotometricWebReader};
+use crate::util::geom::{degrees_to_radians, round_angle};
+use crate::{
+    err::Error,
+    io::{ies::lum_opening::IesLuminousOpening, Diagnostics, ParseOptions},
+    photweb::{PhotometricWebReader, PhotometricWebWriter},
+};
 use property::Property;
 use std::{
     default::Default,
+    f64::consts::PI,
     fs::File,
-    io::{BufReader, Read, Write},
+    io::{BufReader, BufWriter, Read, Write},
     path::Path,
     rc::Rc,
 };
@@ -19,11 +24,16 @@ use std::{
 const LAMP_SECTION_START: usize = 27;
 /// The total number of different parameters being read in for each lamp set.
 const N_LAMP_PARAMS: usize = 6;
+/// The tolerance (in degrees) used when comparing C-angles while inferring symmetry.
+const SYMMETRY_ANGLE_TOLERANCE: f64 = 1E-6;
+/// The number of decimal places angles are rounded to when converting from a photometric web.
+const WRITE_ANGLE_DECIMALS: i32 = 6;
 
 #[allow(dead_code)]
-#[derive(Default, Debug, Clone, Property, PartialEq)]
+#[derive(Debug, Clone, Property, PartialEq)]
 pub struct EulumdatFile {
     /// The first line of the file. Contains company identification / data bank / version / format identification.
+    #[property(get(public), set(public))]
     header: String,
     /// The type indicator.
     ltype: EulumdatType,
@@ -39,14 +49,19 @@ pub struct EulumdatFile {
     /// Dg - Distance between luminous intensities per C-Plane.
     distance_between_luminous_intensities_per_cplane: f64,
     /// Measurement report number.
+    #[property(get(public), set(public))]
     measurement_report_number: String,
     /// The name of the lumminaire.
+    #[property(get(public), set(public))]
     luminaire_name: String,
     /// The lumminaire number.
+    #[property(get(public), set(public))]
     luminaire_number: String,
     /// The filename of the file (as provided by the file).
+    #[property(get(public), set(public))]
     filename: String,
     /// The date / user that created the file.
+    #[property(get(public), set(public))]
     date_user: String,
 
     /** The dimensions of the lumminaire (mm). **/
@@ -75,7 +90,9 @@ pub struct EulumdatFile {
     downward_flux_fraction: f64,
     /// Light output ratio luminaire (LORL) in percent.
     light_output_ratio_luminaire: f64,
-    /// Luminous intensity conversation factor.
+    /// Luminous intensity conversion factor (CFLI), which the stored intensities are multiplied by.
+    /// The intensities are relative to 1000 lm of lamp flux, and the factor corrects them for the
+    /// measurement conditions, so it is normally 1.
     luminous_intensity_conversion_factor: f64,
     /// The tilt of the lumminaire during measurement.
     tilt: f64,
@@ -105,6 +122,20 @@ pub struct EulumdatFile {
     g_angles: Vec<f64>,
     /// Luminous intensities.
     intensities: Vec<f64>,
+
+    /// The options used when parsing the file.
+    options: ParseOptions,
+    /// Non-fatal issues found when parsing, such as unknown codes skipped when
+    /// parsing leniently, or a declared symmetry that does not match the stored data.
+    #[property(get(public), set(disable))]
+    diagnostics: Diagnostics,
+    /// The raw text of each line of the parsed file, kept when requested by the parse options.
+    #[property(get(public), set(disable))]
+    raw_lines: Vec<String>,
+    /// Whether to offset the gamma angles of the planes by the measurement tilt when converting to planes.
+    /// This is off by default, as described in `get_planes`.
+    #[property(get(public), set(public))]
+    apply_tilt: bool,
 }
 
 impl EulumdatFile {
@@ -115,24 +146,175 @@ impl EulumdatFile {
         }
     }
 
+    /// Builds a EULUMDAT file from a photometric web, storing every plane in the web without symmetry,
+    /// or as symmetric about the vertical axis for a spherically symmetric web. The G-angles are taken
+    /// from the first plane, so the planes are assumed to share the same gamma angles. EULUMDAT stores
+    /// intensities relative to 1000 lm of lamp flux, so planes in candela are divided by the rated lumens
+    /// of the web / 1000, which are written to a single lamp set along with the input power. If the web has
+    /// no rated lumens, its own flux is used as the lamp flux, giving a light output ratio of 100%. Planes
+    /// already in `IntensityUnits::CandelaPerKilolumen` are written unchanged.
+    /// The header fields, such as the luminaire name, are left empty so that they can be set before the file
+    /// is written with `to_file`.
+    pub fn from_photometric_web(photweb: &PhotometricWeb) -> EulumdatFile {
+        let c_angles: Vec<f64> = photweb
+            .planes()
+            .iter()
+            .map(|pl| round_angle(pl.angle_deg(), WRITE_ANGLE_DECIMALS))
+            .collect();
+        let g_angles = match photweb.planes().first() {
+            Some(plane) => plane
+                .angles_deg()
+                .iter()
+                .map(|ang| round_angle(*ang, WRITE_ANGLE_DECIMALS))
+                .collect(),
+            None => Vec::new(),
+        };
+        // The spacing is only meaningful if the angles are equally spaced, else it is left as zero.
+        let spacing = |angles: &[f64]| match angles {
+            [first, second, ..]
+                if angles.windows(2).all(|pair| {
+                    (pair[1] - pair[0] - (second - first)).abs() <= SYMMETRY_ANGLE_TOLERANCE
+                }) =>
+            {
+                second - first
+            }
+            _ => 0.0,
+        };
+
+        // Absolute photometry is converted to cd/klm using the lamp flux, which falls back to the flux of the
+        // web itself if it has no rated lumens.
+        let total_flux = photweb.total_flux();
+        let absolute = photweb
+            .planes()
+            .iter()
+            .all(|pl| *pl.units() == IntensityUnits::Candela);
+        let lamp_lumens = match photweb.total_rated_lumens() {
+            Some(lumens) if lumens > 0.0 => lumens,
+            _ if absolute => total_flux,
+            _ => 0.0,
+        };
+        let to_relative = if lamp_lumens > 0.0 {
+            1000.0 / lamp_lumens
+        } else {
+            1.0
+        };
+        let relative_flux = if absolute {
+            total_flux * to_relative
+        } else {
+            total_flux
+        };
+
+        let (downward, upward) = photweb.hemisphere_flux();
+        let mut lamp_set = LampSet::new();
+        lamp_set
+            .set_n_lamps(1)
+            .set_total_luminous_flux(lamp_lumens)
+            .set_wattage(photweb.input_watts().unwrap_or(0.0));
+
+        let mut eul = EulumdatFile {
+            symmetry: if photweb.is_spherically_symmetric() {
+                EulumdatSymmetry::AboutVerticalAxis
+            } else {
+                EulumdatSymmetry::NoSymmetry
+            },
+            n_cplanes: c_angles.len(),
+            cplane_dist: spacing(&c_angles),
+            n_luminous_intensities_per_cplane: g_angles.len(),
+            distance_between_luminous_intensities_per_cplane: spacing(&g_angles),
+            downward_flux_fraction: if downward + upward > 0.0 {
+                100.0 * downward / (downward + upward)
+            } else {
+                0.0
+            },
+            // The relative flux is in lumens per 1000 lm of lamp flux, so gives the ratio as a percentage / 10.
+            light_output_ratio_luminaire: relative_flux / 10.0,
+            luminous_intensity_conversion_factor: 1.0,
+            direct_ratios: vec![0.0; 10],
+            c_angles,
+            g_angles,
+            intensities: photweb
+                .planes()
+                .iter()
+                .flat_map(|pl| {
+                    let scale = match pl.units() {
+                        IntensityUnits::Candela => to_relative,
+                        IntensityUnits::CandelaPerKilolumen => 1.0,
+                    };
+                    pl.intensities().iter().map(move |int| int * scale)
+                })
+                .collect(),
+            ..Default::default()
+        };
+        eul.set_lamp_sets(vec![lamp_set]);
+        eul
+    }
+
     /// A wrapper around the parsing code, that opens a file and reads it.
     pub fn parse_file(filepath: &Path) -> Result<EulumdatFile, Error> {
-        let infile = File::open(filepath)?;
+        let infile = File::open(filepath).map_err(|err| Error::file_error(filepath, err))?;
         let mut ldt_string_buf = String::new();
-        BufReader::new(infile).read_to_string(&mut ldt_string_buf)?;
+        BufReader::new(infile)
+            .read_to_string(&mut ldt_string_buf)
+            .map_err(|err| Error::file_error(filepath, err))?;
         let mut ldt = EulumdatFile::new();
         ldt.parse(&ldt_string_buf)?;
         Ok(ldt)
     }
 
-    /// Attempts to parse an input file.
-    pub fn parse(&mut self, ldt_string: &String) -> Result<(), Error> {
+    /// Sets whether the parser should be lenient, replacing unknown type and symmetry codes
+    /// with their defaults and recording a warning instead of returning an error.
+    ///
+    /// ```
+    /// use lidrs::io::eulumdat::EulumdatFile;
+    ///
+    /// // Replace the symmetry code on the third line with an unknown code.
+    /// let file = std::fs::read_to_string("src/io/eulumdat/example.ldt").unwrap();
+    /// let lines: Vec<&str> = file
+    ///     .lines()
+    ///     .enumerate()
+    ///     .map(|(i, line)| if i == 2 { "7" } else { line })
+    ///     .collect();
+    ///
+    /// let mut ldt = EulumdatFile::new();
+    /// ldt.set_lenient(true);
+    /// ldt.parse(&lines.join("\n")).unwrap();
+    /// assert_eq!(ldt.diagnostics().len(), 1);
+    /// assert_eq!(ldt.diagnostics()[0].line(), Some(3));
+    /// ```
+    pub fn set_lenient(&mut self, lenient: bool) {
+        self.options.set_strict(!lenient);
+    }
+
+    /// Attempts to parse an input file, using the current parse options (strict by default).
+    pub fn parse(&mut self, ldt_string: &str) -> Result<(), Error> {
+        self.parse_with_options(ldt_string, self.options.clone())
+    }
+
+    /// Attempts to parse an input file, using the provided parse options.
+    pub fn parse_with_options(
+        &mut self,
+        ldt_string: &str,
+        options: ParseOptions,
+    ) -> Result<(), Error> {
+        let ldt_string = &crate::io::normalise_text(ldt_string);
+        let strict = options.strict();
+        self.raw_lines = if options.keep_raw_lines() {
+            ldt_string.lines().map(String::from).collect()
+        } else {
+            Vec::new()
+        };
+        self.options = options;
+        self.diagnostics.clear();
+
         // Get all of the lines as a Vec, trimming the whitespace where required.
         let lines: Vec<(usize, String)> = ldt_string
             .lines()
             .enumerate()
             .map(|(iline, str)| (iline + 1, String::from(str.trim())))
             .collect();
+        if lines.iter().all(|(_, line)| line.is_empty()) {
+            return Err(ldt_err::Error::EmptyFile.into());
+        }
 
         // Now parse each of the lines of the file in turn into the struct.
         // As we go along, we will return `Ok(())` if all is good, or an error
@@ -141,13 +323,9 @@ impl EulumdatFile {
         let errs: Vec<ldt_err::Error> = lines
             .iter()
             .map(|(iline, line)| self.process_line(iline, line))
-            .filter_map(|res| {
-                if res.is_err() {
-                    Some(res.unwrap_err())
-                } else {
-                    None
-                }
-            })
+            .filter_map(Result::err)
+            // When not being strict, ignore any extra lines beyond the end of the file.
+            .filter(|err| strict || !matches!(err, ldt_err::Error::TooManyLines(_)))
             .collect();
 
         // Check if we have any errors queued. If so, let's return it.
@@ -155,9 +333,33 @@ impl EulumdatFile {
             return Err(errs.first().unwrap().clone().into());
         }
 
+        // Check that the stored data is consistent with the declared symmetry.
+        let inferred = self.infer_symmetry();
+        if inferred != self.symmetry {
+            self.diagnostics.push(
+                None,
+                &format!(
+                    "Declared symmetry {:?} does not match the stored C-angles and intensities, which imply {:?}.",
+                    self.symmetry, inferred
+                ),
+            );
+        }
+
         Ok(())
     }
 
+    /// Describes an error returned when parsing this file. If the raw lines were kept when parsing,
+    /// as set by `ParseOptions::set_keep_raw_lines`, the text of the line the error occurred on is quoted.
+    pub fn describe_error(&self, err: &Error) -> String {
+        // Line numbers are counted from one.
+        match err.line() {
+            Some(iline) if iline >= 1 && iline <= self.raw_lines.len() => {
+                format!("{}\n{} | {}", err, iline, self.raw_lines[iline - 1])
+            }
+            _ => err.to_string(),
+        }
+    }
+
     /// Is responsible for processing the lines of the file, and parsing values where necessary.
     pub fn process_line(&mut self, iline: &usize, line: &str) -> Result<(), ldt_err::Error> {
         match *iline {
@@ -173,6 +375,17 @@ impl EulumdatFile {
                         self.ltype = ltype;
                         Ok(())
                     }
+                    Err(_) if !self.options.strict() => {
+                        self.ltype = EulumdatType::default();
+                        self.diagnostics.push(
+                            Some(*iline),
+                            &format!(
+                                "Unknown type indicator {}, defaulting to {:?}.",
+                                val, self.ltype
+                            ),
+                        );
+                        Ok(())
+                    }
                     Err(err) => Err(ldt_err::Error::FromPrimitiveError(*iline, Rc::new(err))),
                 },
                 Err(err) => Err(ldt_err::Error::ParseIntError(*iline, err)),
@@ -184,6 +397,17 @@ impl EulumdatFile {
                         self.symmetry = sym;
                         Ok(())
                     }
+                    Err(_) if !self.options.strict() => {
+                        self.symmetry = EulumdatSymmetry::default();
+                        self.diagnostics.push(
+                            Some(*iline),
+                            &format!(
+                                "Unknown symmetry indicator {}, defaulting to {:?}.",
+                                val, self.symmetry
+                            ),
+                        );
+                        Ok(())
+                    }
                     Err(err) => Err(ldt_err::Error::FromPrimitiveError(*iline, Rc::new(err))),
                 },
                 Err(err) => Err(ldt_err::Error::ParseIntError(*iline, err)),
@@ -359,22 +583,12 @@ impl EulumdatFile {
             && iline < LAMP_SECTION_START + (isect + 1) * self.n_lamp_sets
     }
 
-    /// Provides the current index in the current parameter for the lamp set.
-    fn i_lamp_section(&self, iline: usize, isect: usize) -> usize {
-        iline - (LAMP_SECTION_START + isect * self.n_lamp_sets)
-    }
-
     /// Filters out the lines that contain C-angles.
     fn is_c_angles(&self, iline: usize) -> bool {
         iline >= LAMP_SECTION_START + N_LAMP_PARAMS * self.n_lamp_sets + 10
             && iline < LAMP_SECTION_START + N_LAMP_PARAMS * self.n_lamp_sets + 10 + self.n_cplanes
     }
 
-    /// Provides the current index of the C-angle at this line.
-    fn i_c_angle(&self, iline: usize) -> usize {
-        iline - (LAMP_SECTION_START + N_LAMP_PARAMS * self.n_lamp_sets + 10)
-    }
-
     /// Filter out the lines that contain G-angles.
     fn is_g_angles(&self, iline: usize) -> bool {
         iline >= LAMP_SECTION_START + N_LAMP_PARAMS * self.n_lamp_sets + 10 + self.n_cplanes
@@ -386,11 +600,6 @@ impl EulumdatFile {
                     + self.n_luminous_intensities_per_cplane
     }
 
-    /// Provides the current index of the G-angle at this line.
-    fn i_g_angle(&self, iline: usize) -> usize {
-        iline - (LAMP_SECTION_START + N_LAMP_PARAMS * self.n_lamp_sets + 10 + self.n_cplanes)
-    }
-
     /// Filter out the lines that contain the luminous intensities.
     fn is_luminous_intensities(&self, iline: usize) -> bool {
         iline
@@ -399,16 +608,7 @@ impl EulumdatFile {
                 + 10
                 + self.n_cplanes
                 + self.n_luminous_intensities_per_cplane
-    }
-
-    /// Filter out the lines that contain the luminous intensities.
-    fn i_luminous_intensity(&self, iline: usize) -> usize {
-        iline
-            - (LAMP_SECTION_START
-                + N_LAMP_PARAMS * self.n_lamp_sets
-                + 10
-                + self.n_cplanes
-                + self.n_luminous_intensities_per_cplane)
+            && iline < self.n_file_lines()
     }
 
     /// The Mc1 parameter, as defined by the spec.
@@ -433,6 +633,48 @@ impl EulumdatFile {
         }
     }
 
+    /// The number of C-planes stored in the file, from Mc1 to Mc2 inclusive. Mc2 is only less than Mc1
+    /// for a file without any C-planes, so this is ordered to give zero rather than underflowing.
+    fn n_stored_cplanes(&self) -> usize {
+        self.mc2() + 1 - self.mc1()
+    }
+
+    /// Deduces the symmetry of the file from the stored C-angles and the number of stored luminous
+    /// intensities. The declared symmetry indicator is only used to find the first stored C-plane
+    /// when a C90-C270 file lists all of its C-angles, in the same way as `get_planes`,
+    /// so that C-angles running from C0 to C180 imply `C0C180Plane`, C90 to C270 implies
+    /// `C90C270Plane`, C0 to C90 implies `C0C180C90C270Plane` and a single stored plane
+    /// implies `AboutVerticalAxis`. Anything else is treated as `NoSymmetry`.
+    pub fn infer_symmetry(&self) -> EulumdatSymmetry {
+        if self.n_luminous_intensities_per_cplane == 0 {
+            return EulumdatSymmetry::NoSymmetry;
+        }
+
+        let n_stored = self.intensities.len() / self.n_luminous_intensities_per_cplane;
+        let stored_angles: Vec<f64> = self
+            .c_angles
+            .iter()
+            .skip(self.first_stored_c_angle())
+            .take(n_stored)
+            .cloned()
+            .collect();
+        let angle_eq = |a: f64, b: f64| (a - b).abs() < SYMMETRY_ANGLE_TOLERANCE;
+
+        match (stored_angles.first(), stored_angles.last()) {
+            _ if n_stored == 1 => EulumdatSymmetry::AboutVerticalAxis,
+            (Some(&first), Some(&last)) if angle_eq(first, 0.0) && angle_eq(last, 90.0) => {
+                EulumdatSymmetry::C0C180C90C270Plane
+            }
+            (Some(&first), Some(&last)) if angle_eq(first, 0.0) && angle_eq(last, 180.0) => {
+                EulumdatSymmetry::C0C180Plane
+            }
+            (Some(&first), Some(&last)) if angle_eq(first, 90.0) && angle_eq(last, 270.0) => {
+                EulumdatSymmetry::C90C270Plane
+            }
+            _ => EulumdatSymmetry::NoSymmetry,
+        }
+    }
+
     /// Get the expected number of lines in the file.
     fn n_file_lines(&self) -> usize {
         LAMP_SECTION_START // The fixed length parameter section of the file. 
@@ -440,24 +682,327 @@ impl EulumdatFile {
         + 10 // The fixed-length (10-long) direct indices section.
         + self.n_cplanes // The number C-plane angles. 
         + self.n_luminous_intensities_per_cplane // The G-plane angles. 
-        + (self.mc2() - self.mc1() + 1) * self.n_luminous_intensities_per_cplane()
+        + self.n_stored_cplanes() * self.n_luminous_intensities_per_cplane()
         // The number of luminous intensities, which is dependent on the symmetry of the object.
     }
 
+    /// Returns the filename stored in the file with any directory components removed.
+    /// Both Windows (`\`) and Unix (`/`) separators are stripped, as are drive prefixes
+    /// such as `C:`, so `C:\photometry\lamp.ldt` becomes `lamp.ldt`.
+    pub fn filename_basename(&self) -> &str {
+        self.filename
+            .rsplit(['\\', '/', ':'])
+            .next()
+            .unwrap_or("")
+    }
+
     /// Writes the currently loaded EULUMDAT file to a specified file.
-    /// The written value is determined by `EulumdatFile::to_string(&self)`.
+    /// The file is streamed through a buffered writer, as described by `EulumdatFile::write_to`.
     pub fn to_file(&self, outpath: &Path) -> Result<(), Error> {
-        let mut file = File::create(outpath)?;
-        file.write(self.to_string().as_bytes())?;
+        let mut writer = BufWriter::new(File::create(outpath)?);
+        self.write_to(&mut writer)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Writes the EULUMDAT file line by line to the given writer, without building the whole file in memory.
+    /// The fixed-length header is written first, followed by each of the variable-length sections in turn.
+    /// This produces the same output as `EulumdatFile::to_string(&self)`.
+    /// Returns an `InvalidData` error, before writing anything, if any of the variable-length sections hold
+    /// fewer values than the counts in the header require.
+    pub fn write_to<W: Write>(&self, w: &mut W) -> std::io::Result<()> {
+        // Check that each of the variable-length sections is long enough, before writing anything.
+        let n_sets = self.n_lamp_sets;
+        let n_lamp = Self::leading(&self.n_lamp, n_sets, "lamp counts")?;
+        let lamp_type = Self::leading(&self.lamp_type, n_sets, "lamp types")?;
+        let tot_luminous_flux = Self::leading(&self.tot_luminous_flux, n_sets, "lamp fluxes")?;
+        let color_temperature =
+            Self::leading(&self.color_temperature, n_sets, "colour temperatures")?;
+        let color_rendering_group = Self::leading(
+            &self.color_rendering_group,
+            n_sets,
+            "colour rendering groups",
+        )?;
+        let wattage = Self::leading(&self.wattage, n_sets, "lamp wattages")?;
+        let direct_ratios = Self::leading(&self.direct_ratios, 10, "direct ratios")?;
+        let c_angles = Self::leading(&self.c_angles, self.n_cplanes, "C-angles")?;
+        let g_angles = Self::leading(
+            &self.g_angles,
+            self.n_luminous_intensities_per_cplane,
+            "G-angles",
+        )?;
+        let intensities = Self::leading(
+            &self.intensities,
+            self.n_stored_cplanes() * self.n_luminous_intensities_per_cplane,
+            "luminous intensities",
+        )?;
+
+        // The fixed-length parameter section of the file.
+        writeln!(w, "{}", self.header)?;
+        writeln!(w, "{}", self.ltype.clone() as usize)?;
+        writeln!(w, "{}", self.symmetry.clone() as usize)?;
+        writeln!(w, "{}", self.n_cplanes)?;
+        writeln!(w, "{}", self.cplane_dist)?;
+        writeln!(w, "{}", self.n_luminous_intensities_per_cplane)?;
+        writeln!(
+            w,
+            "{}",
+            self.distance_between_luminous_intensities_per_cplane
+        )?;
+        writeln!(w, "{}", self.measurement_report_number)?;
+        writeln!(w, "{}", self.luminaire_name)?;
+        writeln!(w, "{}", self.luminaire_number)?;
+        writeln!(w, "{}", self.filename)?;
+        writeln!(w, "{}", self.date_user)?;
+        writeln!(w, "{}", self.luminaire_length)?;
+        writeln!(w, "{}", self.luminaire_width)?;
+        writeln!(w, "{}", self.luminaire_height)?;
+        writeln!(w, "{}", self.luminous_area_length)?;
+        writeln!(w, "{}", self.luminous_area_width)?;
+        writeln!(w, "{}", self.luminous_area_height_c0)?;
+        writeln!(w, "{}", self.luminous_area_height_c90)?;
+        writeln!(w, "{}", self.luminous_area_height_c180)?;
+        writeln!(w, "{}", self.luminous_area_height_c270)?;
+        writeln!(w, "{}", self.downward_flux_fraction)?;
+        writeln!(w, "{}", self.light_output_ratio_luminaire)?;
+        writeln!(w, "{}", self.luminous_intensity_conversion_factor)?;
+        writeln!(w, "{}", self.tilt)?;
+        writeln!(w, "{}", self.n_lamp_sets)?;
+
+        // The lamp sets, with each parameter given for every set before moving on to the next.
+        Self::write_lines(w, n_lamp)?;
+        Self::write_lines(w, lamp_type)?;
+        Self::write_lines(w, tot_luminous_flux)?;
+        Self::write_lines(w, color_temperature)?;
+        Self::write_lines(w, color_rendering_group)?;
+        Self::write_lines(w, wattage)?;
+
+        // The fixed-length (10-long) direct ratios, followed by the angles and the stored intensities.
+        Self::write_lines(w, direct_ratios)?;
+        Self::write_lines(w, c_angles)?;
+        Self::write_lines(w, g_angles)?;
+        Self::write_lines(w, intensities)
+    }
+
+    /// Returns the first `n` values, or an `InvalidData` error naming the section if there are fewer than `n`.
+    fn leading<'a, T>(values: &'a [T], n: usize, section: &str) -> std::io::Result<&'a [T]> {
+        values.get(..n).ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("Expected {} {}, but found {}.", n, section, values.len()),
+            )
+        })
+    }
+
+    /// Writes each of the values to the given writer on its own line.
+    fn write_lines<W: Write, T: std::fmt::Display>(w: &mut W, values: &[T]) -> std::io::Result<()> {
+        for val in values {
+            writeln!(w, "{}", val)?;
+        }
         Ok(())
     }
 
+    /// Returns the area of the luminous opening in square metres. The file stores the dimensions in
+    /// millimetres, and a width of zero indicates a circular opening with the length as its diameter.
+    pub fn luminous_area(&self) -> f64 {
+        let area_mm2 = if self.luminous_area_width == 0.0 {
+            PI * (0.5 * self.luminous_area_length).powi(2)
+        } else {
+            self.luminous_area_length * self.luminous_area_width
+        };
+        area_mm2 * 1E-6
+    }
+
+    /// Returns the aspect ratio (length / width) of the luminous opening.
+    /// A circular opening, which is stored with a width of zero, has an aspect ratio of one.
+    pub fn aspect_ratio(&self) -> f64 {
+        if self.luminous_area_width == 0.0 {
+            1.0
+        } else {
+            self.luminous_area_length / self.luminous_area_width
+        }
+    }
+
+    /// Returns the lamp sets in the file, gathering each set's parameters from the parallel lamp vectors.
+    pub fn lamp_sets(&self) -> Vec<LampSet> {
+        (0..self.n_lamp_sets)
+            .filter_map(|i| {
+                let mut lamp_set = LampSet::new();
+                lamp_set
+                    .set_n_lamps(*self.n_lamp.get(i)?)
+                    .set_lamp_type(self.lamp_type.get(i)?)
+                    .set_total_luminous_flux(*self.tot_luminous_flux.get(i)?)
+                    .set_color_temperature(self.color_temperature.get(i)?)
+                    .set_color_rendering_group(self.color_rendering_group.get(i)?)
+                    .set_wattage(*self.wattage.get(i)?);
+                Some(lamp_set)
+            })
+            .collect()
+    }
+
+    /// Replaces the lamp sets in the file, splitting each set's parameters into the parallel lamp vectors.
+    pub fn set_lamp_sets(&mut self, lamp_sets: Vec<LampSet>) {
+        self.n_lamp_sets = lamp_sets.len();
+        self.n_lamp = lamp_sets.iter().map(|set| set.n_lamps()).collect();
+        self.lamp_type = lamp_sets
+            .iter()
+            .map(|set| set.lamp_type().to_owned())
+            .collect();
+        self.tot_luminous_flux = lamp_sets
+            .iter()
+            .map(|set| set.total_luminous_flux())
+            .collect();
+        self.color_temperature = lamp_sets
+            .iter()
+            .map(|set| set.color_temperature().to_owned())
+            .collect();
+        self.color_rendering_group = lamp_sets
+            .iter()
+            .map(|set| set.color_rendering_group().to_owned())
+            .collect();
+        self.wattage = lamp_sets.iter().map(|set| set.wattage()).collect();
+    }
+
+    /// Trims a full set of intensities, stored without symmetry, down to the C-planes that represent
+    /// the given symmetry, as the reverse of the expansion performed by `get_planes`. The C-angles are
+    /// kept for all of the C-planes, as they are written to the file in full. The intensities are not
+    /// checked against the symmetry, so the caller should ensure that the symmetry is satisfied.
+    /// Returns an error if the file already has a symmetry applied, or doesn't store every C-plane.
+    pub fn apply_symmetry(&mut self, symmetry: EulumdatSymmetry) -> Result<(), Error> {
+        if self.symmetry != EulumdatSymmetry::NoSymmetry {
+            return Err(ldt_err::Error::SymmetryAlreadyApplied(self.symmetry.clone()).into());
+        }
+        let n_stored = self.intensities.len() / self.n_luminous_intensities_per_cplane.max(1);
+        if n_stored != self.n_cplanes || self.c_angles.len() != self.n_cplanes {
+            return Err(ldt_err::Error::IncompleteCPlanes(self.n_cplanes, n_stored).into());
+        }
+
+        // Find the C-planes within the range that represents the symmetry.
+        let (lower, upper) = match symmetry {
+            EulumdatSymmetry::NoSymmetry => return Ok(()),
+            EulumdatSymmetry::AboutVerticalAxis => (0.0, 0.0),
+            EulumdatSymmetry::C0C180Plane => (0.0, 180.0),
+            EulumdatSymmetry::C90C270Plane => (90.0, 270.0),
+            EulumdatSymmetry::C0C180C90C270Plane => (0.0, 90.0),
+        };
+        let in_range: Vec<usize> = self
+            .c_angles
+            .iter()
+            .enumerate()
+            .filter(|(_, ang)| {
+                **ang >= lower - SYMMETRY_ANGLE_TOLERANCE
+                    && **ang <= upper + SYMMETRY_ANGLE_TOLERANCE
+            })
+            .map(|(i, _)| i)
+            .collect();
+
+        let previous_symmetry = std::mem::replace(&mut self.symmetry, symmetry);
+        let n_expected = self.n_stored_cplanes();
+        if in_range.len() != n_expected {
+            self.symmetry = previous_symmetry;
+            return Err(ldt_err::Error::InvalidSymmetryPlanes(n_expected, in_range.len()).into());
+        }
+
+        let ng = self.n_luminous_intensities_per_cplane;
+        self.intensities = in_range
+            .iter()
+            .flat_map(|i| self.intensities[i * ng..(i + 1) * ng].iter().copied())
+            .collect();
+        Ok(())
+    }
+
+    /// Returns the index of the C-angle of the first stored C-plane. This is zero, unless the file has
+    /// C90-C270 symmetry and lists the C-angles of all of the C-planes, in which case the stored C-planes
+    /// start from C90.
+    fn first_stored_c_angle(&self) -> usize {
+        let n_stored = self.intensities.len() / self.n_luminous_intensities_per_cplane.max(1);
+        if self.symmetry != EulumdatSymmetry::C90C270Plane || self.c_angles.len() <= n_stored {
+            return 0;
+        }
+        self.c_angles
+            .iter()
+            .position(|ang| (ang - 90.0).abs() < SYMMETRY_ANGLE_TOLERANCE)
+            .unwrap_or(0)
+    }
+
+    /// Converts the file into a photometric web, returning an error if the planes cannot be built.
+    pub fn to_photometric_web(&self) -> Result<PhotometricWeb, ldt_err::Error> {
+        let mut photweb = PhotometricWeb::new();
+        // Get the angles.
+        photweb.set_planes(self.get_planes()?);
+        // The total input power is the sum of the wattage of each of the lamp sets.
+        let watts: f64 = self.wattage.iter().sum();
+        if watts > 0.0 {
+            photweb.set_input_watts(watts);
+        }
+        // Likewise, the rated flux is the sum of the flux of each of the lamp sets.
+        let lumens = self.total_lamp_flux();
+        if lumens > 0.0 {
+            photweb.set_total_rated_lumens(lumens);
+        }
+        // The luminous area is stored in millimetres, with a width of zero for a circular area.
+        if self.luminous_area_length > 0.0 {
+            photweb.set_luminous_opening(if self.luminous_area_width == 0.0 {
+                IesLuminousOpening::Circular {
+                    diameter: self.luminous_area_length * 1E-3,
+                }
+            } else {
+                IesLuminousOpening::Rectangular {
+                    width: self.luminous_area_width * 1E-3,
+                    length: self.luminous_area_length * 1E-3,
+                }
+            });
+        }
+        // The heights of the luminous area differ by direction for linear luminaires in particular.
+        let heights = [
+            self.luminous_area_height_c0,
+            self.luminous_area_height_c90,
+            self.luminous_area_height_c180,
+            self.luminous_area_height_c270,
+        ];
+        if heights.iter().any(|height| *height > 0.0) {
+            photweb.set_luminous_area_heights(heights.map(|height| height * 1E-3));
+        }
+        Ok(photweb)
+    }
+
+    /// The total luminous flux of the lamps in all of the lamp sets, in lumens.
+    fn total_lamp_flux(&self) -> f64 {
+        self.tot_luminous_flux.iter().sum()
+    }
+
     /// Gets the planes from this file.
-    pub fn get_planes(&self) -> Vec<Plane> {
+    /// If `apply_tilt` is set, the tilt of the luminaire during measurement (in degrees) is added to each of the
+    /// gamma angles, so a positive tilt moves every sample away from nadir by the tilt angle. Otherwise, the
+    /// gamma angles are used as stored.
+    /// The stored intensities are multiplied by the luminous intensity conversion factor. They are stored relative
+    /// to 1000 lm of lamp flux (cd/klm), so are also scaled by the total flux of the lamp sets to give candela.
+    /// If the lamp flux is not known, the intensities are left relative, and the planes are marked as being in
+    /// `IntensityUnits::CandelaPerKilolumen`.
+    /// Returns an error if there are no gamma angles to divide the intensities into planes.
+    pub fn get_planes(&self) -> Result<Vec<Plane>, ldt_err::Error> {
+        if self.n_luminous_intensities_per_cplane == 0 {
+            return Err(ldt_err::Error::NoGammaAngles);
+        }
+
+        let lamp_flux = self.total_lamp_flux();
+        let (scale, units) = if lamp_flux > 0.0 {
+            (
+                self.luminous_intensity_conversion_factor * lamp_flux / 1000.0,
+                IntensityUnits::Candela,
+            )
+        } else {
+            (
+                self.luminous_intensity_conversion_factor,
+                IntensityUnits::CandelaPerKilolumen,
+            )
+        };
+
+        let tilt_deg = if self.apply_tilt { self.tilt } else { 0.0 };
         let mut planes: Vec<Plane> = self
             .intensities
             .chunks(self.n_luminous_intensities_per_cplane)
-            .zip(self.c_angles.iter())
+            .zip(self.c_angles.iter().skip(self.first_stored_c_angle()))
             .map(|(intens, c_angle)| {
                 let mut pl = Plane::new();
                 // First set the angle.
@@ -466,13 +1011,13 @@ impl EulumdatFile {
                 pl.set_angles(
                     self.g_angles
                         .iter()
-                        .map(|ang_deg| degrees_to_radians(*ang_deg))
+                        .map(|ang_deg| degrees_to_radians(*ang_deg + tilt_deg))
                         .collect::<Vec<f64>>(),
                 );
-                // Set the intensities from this chunk of angles.
-                pl.set_intensities(Vec::from(intens));
+                // Set the intensities from this chunk of angles, converting them to candela where possible.
+                pl.set_intensities(intens.iter().map(|val| val * scale).collect::<Vec<f64>>());
                 // Set the flux units of the intensities.
-                pl.set_units(IntensityUnits::Candela);
+                pl.set_units(units.clone());
                 // Set the units of the units.
                 pl.set_orientation(PlaneOrientation::Vertical);
 
@@ -498,107 +1043,120 @@ impl EulumdatFile {
             planes = mirror_second_and_third_quadrants(&planes);
         }
 
-        planes
+        Ok(planes)
     }
 }
 
-impl ToString for EulumdatFile {
+impl Default for EulumdatFile {
+    /// Returns an empty file, with a luminous intensity conversion factor of 1 so that the intensities are unscaled.
+    fn default() -> Self {
+        EulumdatFile {
+            header: String::new(),
+            ltype: EulumdatType::default(),
+            symmetry: EulumdatSymmetry::default(),
+            n_cplanes: 0,
+            cplane_dist: 0.0,
+            n_luminous_intensities_per_cplane: 0,
+            distance_between_luminous_intensities_per_cplane: 0.0,
+            measurement_report_number: String::new(),
+            luminaire_name: String::new(),
+            luminaire_number: String::new(),
+            filename: String::new(),
+            date_user: String::new(),
+            luminaire_length: 0.0,
+            luminaire_width: 0.0,
+            luminaire_height: 0.0,
+            luminous_area_length: 0.0,
+            luminous_area_width: 0.0,
+            luminous_area_height_c0: 0.0,
+            luminous_area_height_c90: 0.0,
+            luminous_area_height_c180: 0.0,
+            luminous_area_height_c270: 0.0,
+            downward_flux_fraction: 0.0,
+            light_output_ratio_luminaire: 0.0,
+            luminous_intensity_conversion_factor: 1.0,
+            tilt: 0.0,
+            n_lamp_sets: 0,
+            n_lamp: Vec::new(),
+            lamp_type: Vec::new(),
+            tot_luminous_flux: Vec::new(),
+            color_temperature: Vec::new(),
+            color_rendering_group: Vec::new(),
+            wattage: Vec::new(),
+            direct_ratios: Vec::new(),
+            c_angles: Vec::new(),
+            g_angles: Vec::new(),
+            intensities: Vec::new(),
+            options: ParseOptions::default(),
+            diagnostics: Diagnostics::new(),
+            raw_lines: Vec::new(),
+            apply_tilt: false,
+        }
+    }
+}
+
+impl std::fmt::Display for EulumdatFile {
     /// Writes the object to a EULUMDAT format string, which can be written to a file.
     /// We need to be careful that we limit to the correct size of string, as defined by the spec.
-    fn to_string(&self) -> String {
-        (1..self.n_file_lines())
-            .into_iter()
-            .fold("".to_string(), |accum, iline| {
-                accum
-                    + format!(
-                        "{}\n",
-                        match iline {
-                            1 => self.header.clone(),
-                            2 => (self.ltype.clone() as usize).to_string(),
-                            3 => (self.symmetry.clone() as usize).to_string(),
-                            4 => self.n_cplanes.to_string(),
-                            5 => self.cplane_dist.to_string(),
-                            6 => self.n_luminous_intensities_per_cplane.to_string(),
-                            7 => self
-                                .distance_between_luminous_intensities_per_cplane
-                                .to_string(),
-                            8 => self.measurement_report_number.clone(),
-                            9 => self.luminaire_name.clone(),
-                            10 => self.luminaire_number.clone(),
-                            11 => self.filename.clone(),
-                            12 => self.date_user.clone(),
-                            13 => self.luminaire_length.to_string(),
-                            14 => self.luminaire_width.to_string(),
-                            15 => self.luminaire_height.to_string(),
-                            16 => self.luminous_area_length.to_string(),
-                            17 => self.luminous_area_width.to_string(),
-                            18 => self.luminous_area_height_c0.to_string(),
-                            19 => self.luminous_area_height_c90.to_string(),
-                            20 => self.luminous_area_height_c180.to_string(),
-                            21 => self.luminous_area_height_c270.to_string(),
-                            22 => self.downward_flux_fraction.to_string(),
-                            23 => self.light_output_ratio_luminaire.to_string(),
-                            24 => self.luminous_intensity_conversion_factor.to_string(),
-                            25 => self.tilt.to_string(),
-                            26 => self.n_lamp_sets.to_string(),
-                            i if self.lamp_section(i, 0) => {
-                                self.n_lamp[self.i_lamp_section(iline, 0)].to_string()
-                            }
-                            i if self.lamp_section(i, 1) => {
-                                self.lamp_type[self.i_lamp_section(iline, 1)].clone()
-                            }
-                            i if self.lamp_section(i, 2) => {
-                                self.tot_luminous_flux[self.i_lamp_section(iline, 2)].to_string()
-                            }
-                            i if self.lamp_section(i, 3) => {
-                                self.color_temperature[self.i_lamp_section(iline, 3)].clone()
-                            }
-                            i if self.lamp_section(i, 4) => {
-                                self.color_rendering_group[self.i_lamp_section(iline, 4)].clone()
-                            }
-                            i if self.lamp_section(i, 5) => {
-                                self.wattage[self.i_lamp_section(iline, 5)].to_string()
-                            }
-                            i if i >= LAMP_SECTION_START + N_LAMP_PARAMS * self.n_lamp_sets
-                                && i < LAMP_SECTION_START
-                                    + N_LAMP_PARAMS * self.n_lamp_sets
-                                    + 10 =>
-                            {
-                                let i_direct_ratio =
-                                    iline - (LAMP_SECTION_START + N_LAMP_PARAMS * self.n_lamp_sets);
-                                self.direct_ratios[i_direct_ratio].to_string()
-                            }
-                            i if self.is_c_angles(i) => {
-                                self.c_angles[self.i_c_angle(iline)].to_string()
-                            }
-                            i if self.is_g_angles(i) => {
-                                self.g_angles[self.i_g_angle(iline)].to_string()
-                            }
-                            i if self.is_luminous_intensities(i) => {
-                                self.intensities[self.i_luminous_intensity(iline)].to_string()
-                            }
-                            _ => "".to_owned(),
-                        }
-                    )
-                    .as_ref()
-            })
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut output = Vec::new();
+        self.write_to(&mut output).map_err(|_| std::fmt::Error)?;
+        write!(f, "{}", String::from_utf8_lossy(&output))
     }
 }
 
 impl From<EulumdatFile> for PhotometricWeb {
+    /// Converts the file into a photometric web. A malformed file that has no planes gives an empty web,
+    /// use `EulumdatFile::to_photometric_web` to get the error instead.
     fn from(eul: EulumdatFile) -> Self {
-        let mut photweb = PhotometricWeb::new();
-        // Get the angles.
-        photweb.set_planes(eul.get_planes());
-        photweb
+        eul.to_photometric_web().unwrap_or_default()
+    }
+}
+
+impl From<&PhotometricWeb> for EulumdatFile {
+    fn from(photweb: &PhotometricWeb) -> Self {
+        EulumdatFile::from_photometric_web(photweb)
+    }
+}
+
+impl TryFrom<&str> for EulumdatFile {
+    type Error = Error;
+
+    /// Parses a file from a string, using the default (strict) parse options.
+    fn try_from(file_string: &str) -> Result<Self, Self::Error> {
+        let mut file = EulumdatFile::new();
+        file.parse(file_string)?;
+        Ok(file)
     }
 }
 
 //TODO: Implement conversion.
 impl PhotometricWebReader for EulumdatFile {
     fn read(&self, path: &Path) -> Result<PhotometricWeb, Error> {
-        let eul_file = Self::parse_file(path)?;
-        let photweb: PhotometricWeb = eul_file.into();
+        let (photweb, _) = self.read_with_diagnostics(path)?;
         Ok(photweb)
     }
+
+    fn read_with_diagnostics(&self, path: &Path) -> Result<(PhotometricWeb, Diagnostics), Error> {
+        let eul_file = Self::parse_file(path)?;
+        let diagnostics = eul_file.diagnostics().clone();
+        Ok((eul_file.to_photometric_web()?, diagnostics))
+    }
+
+    fn read_str_with_diagnostics(
+        &self,
+        contents: &str,
+    ) -> Result<(PhotometricWeb, Diagnostics), Error> {
+        let mut eul_file = EulumdatFile::new();
+        eul_file.parse(contents)?;
+        let diagnostics = eul_file.diagnostics().clone();
+        Ok((eul_file.to_photometric_web()?, diagnostics))
+    }
+}
+
+impl PhotometricWebWriter for EulumdatFile {
+    fn write(photweb: &PhotometricWeb, path: &Path) -> Result<(), Error> {
+        photweb.validate()?;
+        EulumdatFile::from_photometric_web(photweb).to_file(path)
+    }
 }
@@ -0,0 +1,29 @@
+use property::Property;
+
+/// A set of standard lamps, as described in the lamp section of a EULUMDAT file.
+/// The file stores each parameter of the lamp sets in turn, so these are gathered together here.
+#[derive(Default, Debug, Clone, PartialEq, Property)]
+#[property(get(public), set(public))]
+pub struct LampSet {
+    /// The number of lamps in the set.
+    n_lamps: i32,
+    /// The type of the lamp.
+    lamp_type: String,
+    /// Total luminous flux of the lamps (lumens).
+    total_luminous_flux: f64,
+    /// Colour appearance / color temperature of the lamps.
+    color_temperature: String,
+    /// Colour rendering group.
+    color_rendering_group: String,
+    /// Wattage including ballast (watts).
+    wattage: f64,
+}
+
+impl LampSet {
+    /// Returns a new instance of the lamp set with default values.
+    pub fn new() -> LampSet {
+        LampSet {
+            ..Default::default()
+        }
+    }
+}
@@ -8,6 +8,9 @@ pub enum EulumdatType {
     PointSourceWithOtherSymmetry = 3,
 }
 
+// Deriving `Default` would mark the variant with `#[default]`, which `TryFromPrimitive`
+// treats as a catch-all for unknown values, so we implement it by hand.
+#[allow(clippy::derivable_impls)]
 impl Default for EulumdatType {
     fn default() -> Self {
         EulumdatType::PointSourceWithSymmetryAboutVerticalAxis
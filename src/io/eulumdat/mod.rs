@@ -8,10 +8,11 @@
 
 pub mod err;
 pub mod eulumdat_file;
+pub mod lamp_set;
 pub mod ltyp;
 pub mod symmetry;
 pub mod util;
-pub use self::{err::*, eulumdat_file::*, ltyp::*, symmetry::*, util::*};
+pub use self::{err::*, eulumdat_file::*, lamp_set::*, ltyp::*, symmetry::*, util::*};
 
 #[cfg(test)]
 pub mod tests;
@@ -10,6 +10,9 @@ pub enum EulumdatSymmetry {
     C0C180C90C270Plane = 4,
 }
 
+// Deriving `Default` would mark the variant with `#[default]`, which `TryFromPrimitive`
+// treats as a catch-all for unknown values, so we implement it by hand.
+#[allow(clippy::derivable_impls)]
 impl Default for EulumdatSymmetry {
     fn default() -> Self {
         EulumdatSymmetry::NoSymmetry
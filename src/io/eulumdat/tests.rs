@@ -1,5 +1,10 @@
-use super::{EulumdatFile, EulumdatSymmetry};
-use crate::photweb::PhotometricWeb;
+use super::{err as ldt_err, EulumdatFile, EulumdatSymmetry, EulumdatType, LampSet};
+use crate::{
+    err::Error,
+    io::ParseOptions,
+    photweb::{IntensityUnits, PhotometricWeb, Plane},
+    util::geom::degrees_to_radians,
+};
 use approx::assert_relative_eq;
 use std::path::Path;
 
@@ -10,7 +15,7 @@ const EXAMPLE_LDT_FILE: &str = include_str!("example.ldt");
 #[test]
 fn test_parse_ldt() {
     let mut ldt = EulumdatFile::new();
-    match ldt.parse(&EXAMPLE_LDT_FILE.to_owned()) {
+    match ldt.parse(EXAMPLE_LDT_FILE) {
         Ok(_) => {
             // Check that the arrays are the correct length.
             assert_eq!(ldt.c_angles().iter().count(), ldt.n_cplanes());
@@ -23,7 +28,7 @@ fn test_parse_ldt() {
                 (ldt.mc2() - ldt.mc1() + 1) * ldt.n_luminous_intensities_per_cplane()
             );
         }
-        Err(e) => assert!(false, "LDT parse error: {}", e),
+        Err(e) => panic!("LDT parse error: {}", e),
     }
 }
 
@@ -42,22 +47,59 @@ fn test_parse_ldt_file() {
                 (ldt.mc2() - ldt.mc1() + 1) * ldt.n_luminous_intensities_per_cplane()
             );
         }
-        Err(e) => assert!(false, "LDT file parse error: {}", e),
+        Err(e) => panic!("LDT file parse error: {}", e),
+    }
+}
+
+/// Check that the lamp sets are gathered from the parallel lamp vectors, and can be written back.
+#[test]
+fn test_lamp_sets() {
+    let mut ldt = EulumdatFile::new();
+    ldt.parse(EXAMPLE_LDT_FILE).unwrap();
+
+    let lamp_sets = ldt.lamp_sets();
+    assert_eq!(lamp_sets.len(), 1);
+    assert_eq!(lamp_sets[0].n_lamps(), 1);
+    assert_eq!(lamp_sets[0].lamp_type(), "");
+    assert_eq!(lamp_sets[0].total_luminous_flux(), 5134.0);
+    assert_eq!(lamp_sets[0].color_temperature(), "3008");
+    assert_eq!(lamp_sets[0].color_rendering_group(), "83.4");
+    assert_eq!(lamp_sets[0].wattage(), 60.22);
+
+    // Add a second set, and check it is stored alongside the first.
+    let mut second = LampSet::new();
+    second.set_n_lamps(2).set_lamp_type("LED").set_wattage(12.5);
+    ldt.set_lamp_sets(vec![lamp_sets[0].clone(), second.clone()]);
+    assert_eq!(ldt.n_lamp_sets(), 2);
+    assert_eq!(ldt.wattage(), &[60.22, 12.5]);
+    assert_eq!(ldt.lamp_sets(), vec![lamp_sets[0].clone(), second]);
+}
+
+/// Check that the path of a file that cannot be opened is reported in the error.
+#[test]
+fn test_parse_ldt_file_missing() {
+    match EulumdatFile::parse_file(Path::new("./src/io/eulumdat/missing.ldt")) {
+        Ok(_) => panic!("Parsing a missing file should fail."),
+        Err(e) => {
+            assert!(matches!(e, Error::FileError { .. }));
+            assert!(e.to_string().contains("missing.ldt"));
+        }
     }
 }
 
 #[test]
 fn test_ldt_into_photweb() {
     let mut ldt = EulumdatFile::new();
-    match ldt.parse(&EXAMPLE_LDT_FILE.to_owned()) {
+    match ldt.parse(EXAMPLE_LDT_FILE) {
         Ok(_) => {
             // Now attempt to convert to a photometric web.
             let photweb: PhotometricWeb = ldt.clone().into();
 
             // Test that the parameters have made it across.
-            assert_eq!(photweb.planes().iter().count() as usize, ldt.n_cplanes());
+            assert_eq!(photweb.planes().iter().count(), ldt.n_cplanes());
+            assert_eq!(photweb.total_rated_lumens(), Some(5134.0));
         }
-        Err(e) => assert!(false, "LDT parse error: {}", e),
+        Err(e) => panic!("LDT parse error: {}", e),
     }
 }
 
@@ -66,7 +108,7 @@ fn test_ldt_into_photweb() {
 #[test]
 fn test_get_planes_c0c180_symmetry() {
     let mut ldt = EulumdatFile::new();
-    ldt.set_n_cplanes(18 as usize);
+    ldt.set_n_cplanes(18_usize);
     ldt.set_c_angles(
         (0..190)
             .step_by(10)
@@ -74,7 +116,7 @@ fn test_get_planes_c0c180_symmetry() {
             .collect::<Vec<f64>>(),
     );
     ldt.set_g_angles(vec![0.0]);
-    ldt.set_n_luminous_intensities_per_cplane(1 as usize);
+    ldt.set_n_luminous_intensities_per_cplane(1_usize);
     ldt.set_intensities(
         (0..190)
             .step_by(10)
@@ -82,6 +124,8 @@ fn test_get_planes_c0c180_symmetry() {
             .collect::<Vec<f64>>(),
     );
     ldt.set_symmetry(EulumdatSymmetry::C0C180Plane);
+    // The stored C-angles and intensities should imply the declared symmetry.
+    assert_eq!(ldt.infer_symmetry(), *ldt.symmetry());
 
     // Perform the conversion.
     let photweb: PhotometricWeb = ldt.clone().into();
@@ -119,7 +163,7 @@ fn test_get_planes_c0c180_symmetry() {
 #[test]
 fn test_get_planes_c90c270_symmetry() {
     let mut ldt = EulumdatFile::new();
-    ldt.set_n_cplanes(18 as usize);
+    ldt.set_n_cplanes(18_usize);
     ldt.set_c_angles(
         (90..280)
             .step_by(10)
@@ -127,7 +171,7 @@ fn test_get_planes_c90c270_symmetry() {
             .collect::<Vec<f64>>(),
     );
     ldt.set_g_angles(vec![0.0]);
-    ldt.set_n_luminous_intensities_per_cplane(1 as usize);
+    ldt.set_n_luminous_intensities_per_cplane(1_usize);
     ldt.set_intensities(
         (90..280)
             .step_by(10)
@@ -135,6 +179,8 @@ fn test_get_planes_c90c270_symmetry() {
             .collect::<Vec<f64>>(),
     );
     ldt.set_symmetry(EulumdatSymmetry::C90C270Plane);
+    // The stored C-angles and intensities should imply the declared symmetry.
+    assert_eq!(ldt.infer_symmetry(), *ldt.symmetry());
     // Perform the conversation.
     let photweb: PhotometricWeb = ldt.clone().into();
 
@@ -170,7 +216,7 @@ fn test_get_planes_c90c270_symmetry() {
 #[test]
 fn test_get_planes_c0c180c90c270_symmetry() {
     let mut ldt = EulumdatFile::new();
-    ldt.set_n_cplanes(9 as usize);
+    ldt.set_n_cplanes(9_usize);
     ldt.set_c_angles(
         (0..100)
             .step_by(10)
@@ -178,7 +224,7 @@ fn test_get_planes_c0c180c90c270_symmetry() {
             .collect::<Vec<f64>>(),
     );
     ldt.set_g_angles(vec![0.0]);
-    ldt.set_n_luminous_intensities_per_cplane(1 as usize);
+    ldt.set_n_luminous_intensities_per_cplane(1_usize);
     ldt.set_intensities(
         (0..100)
             .step_by(10)
@@ -186,6 +232,8 @@ fn test_get_planes_c0c180c90c270_symmetry() {
             .collect::<Vec<f64>>(),
     );
     ldt.set_symmetry(EulumdatSymmetry::C0C180C90C270Plane);
+    // The stored C-angles and intensities should imply the declared symmetry.
+    assert_eq!(ldt.infer_symmetry(), *ldt.symmetry());
 
     // Perform the conversion.
     let photweb: PhotometricWeb = ldt.clone().into();
@@ -222,12 +270,14 @@ fn test_get_planes_c0c180c90c270_symmetry() {
 #[test]
 fn test_get_planes_spherical_symmetry() {
     let mut ldt = EulumdatFile::new();
-    ldt.set_n_cplanes(1 as usize);
+    ldt.set_n_cplanes(1_usize);
     ldt.set_c_angles(vec![0.0]);
     ldt.set_g_angles(vec![0.0]);
-    ldt.set_n_luminous_intensities_per_cplane(1 as usize);
+    ldt.set_n_luminous_intensities_per_cplane(1_usize);
     ldt.set_intensities(vec![1.0]);
     ldt.set_symmetry(EulumdatSymmetry::AboutVerticalAxis);
+    // The stored C-angles and intensities should imply the declared symmetry.
+    assert_eq!(ldt.infer_symmetry(), *ldt.symmetry());
 
     // Perform the conversion.
     let photweb: PhotometricWeb = ldt.clone().into();
@@ -252,3 +302,619 @@ fn test_get_planes_spherical_symmetry() {
         vec![1.0]
     );
 }
+
+/// Check that Windows-style paths in the filename field are reduced to the basename.
+#[test]
+fn test_filename_basename() {
+    let mut ldt = EulumdatFile::new();
+    ldt.set_filename("C:\\photometry\\lamp.ldt");
+    assert_eq!(ldt.filename_basename(), "lamp.ldt");
+
+    ldt.set_filename("/usr/share/photometry/lamp.ldt");
+    assert_eq!(ldt.filename_basename(), "lamp.ldt");
+
+    ldt.set_filename("C:lamp.ldt");
+    assert_eq!(ldt.filename_basename(), "lamp.ldt");
+
+    // The example file stores a bare filename, which should be left untouched.
+    ldt.parse(EXAMPLE_LDT_FILE).unwrap();
+    assert_eq!(
+        ldt.filename_basename(),
+        "Prolicht E30-0019 - AGP + I-Diff 20 Combined.ldt"
+    );
+}
+
+/// Check that extra lines at the end of the file are only accepted when not parsing strictly.
+#[test]
+fn test_parse_options_strict() {
+    let malformed = format!("{}\n\nTrailing notes from the exporter.\n", EXAMPLE_LDT_FILE);
+
+    let mut ldt = EulumdatFile::new();
+    assert!(ldt.parse(&malformed).is_err());
+
+    let mut ldt = EulumdatFile::new();
+    assert!(ldt
+        .parse_with_options(&malformed, ParseOptions::new())
+        .is_err());
+
+    let mut ldt = EulumdatFile::new();
+    match ldt.parse_with_options(&malformed, ParseOptions::lenient()) {
+        Ok(_) => {
+            let mut clean = EulumdatFile::new();
+            clean.parse(EXAMPLE_LDT_FILE).unwrap();
+            assert_eq!(ldt.intensities(), clean.intensities());
+        }
+        Err(e) => panic!("LDT lenient parse error: {}", e),
+    }
+}
+
+/// Check that a web built from a file with a single G-angle can be integrated without panicking.
+#[test]
+fn test_single_gamma_photweb_integrates() {
+    let mut ldt = EulumdatFile::new();
+    ldt.set_n_cplanes(1_usize);
+    ldt.set_c_angles(vec![0.0]);
+    ldt.set_g_angles(vec![0.0]);
+    ldt.set_n_luminous_intensities_per_cplane(1_usize);
+    ldt.set_intensities(vec![1.0]);
+    ldt.set_symmetry(EulumdatSymmetry::AboutVerticalAxis);
+
+    let photweb: PhotometricWeb = ldt.into();
+    assert_eq!(photweb.total_intensity(), 0.0);
+    assert_eq!(photweb.total_flux(), 0.0);
+}
+
+/// Replaces the given (1-indexed) line of the example file.
+fn example_with_line(iline: usize, line: &str) -> String {
+    EXAMPLE_LDT_FILE
+        .lines()
+        .enumerate()
+        .map(|(i, l)| if i + 1 == iline { line } else { l })
+        .collect::<Vec<&str>>()
+        .join("\n")
+}
+
+/// Check that unknown symmetry and type codes are errors when strict, and fall back to defaults when lenient.
+#[test]
+fn test_unknown_codes_lenient() {
+    let unknown_sym = example_with_line(3, "7");
+    let unknown_type = example_with_line(2, "9");
+
+    // Strict is the default.
+    let mut ldt = EulumdatFile::new();
+    assert!(ldt.parse(&unknown_sym).is_err());
+    let mut ldt = EulumdatFile::new();
+    assert!(ldt.parse(&unknown_type).is_err());
+
+    let mut ldt = EulumdatFile::new();
+    ldt.set_lenient(true);
+    match ldt.parse(&unknown_sym) {
+        Ok(_) => {
+            assert_eq!(*ldt.symmetry(), EulumdatSymmetry::NoSymmetry);
+            assert_eq!(ldt.diagnostics().len(), 1);
+            assert!(ldt.diagnostics()[0].line() == Some(3));
+        }
+        Err(e) => panic!("LDT lenient parse error: {}", e),
+    }
+
+    let mut ldt = EulumdatFile::new();
+    ldt.set_lenient(true);
+    match ldt.parse(&unknown_type) {
+        Ok(_) => {
+            assert_eq!(
+                *ldt.ltype(),
+                EulumdatType::PointSourceWithSymmetryAboutVerticalAxis
+            );
+            assert_eq!(ldt.diagnostics().len(), 1);
+        }
+        Err(e) => panic!("LDT lenient parse error: {}", e),
+    }
+
+    // A clean file shouldn't produce any diagnostics.
+    let mut ldt = EulumdatFile::new();
+    ldt.set_lenient(true);
+    ldt.parse(EXAMPLE_LDT_FILE).unwrap();
+    assert!(ldt.diagnostics().is_empty());
+}
+
+/// Check that a declared symmetry that doesn't match the stored C-angles produces a warning.
+#[test]
+fn test_symmetry_mismatch_warning() {
+    let mut ldt = EulumdatFile::new();
+    ldt.parse(EXAMPLE_LDT_FILE).unwrap();
+    assert_eq!(ldt.infer_symmetry(), EulumdatSymmetry::NoSymmetry);
+    assert!(ldt.diagnostics().is_empty());
+
+    // Declare C0-C180 symmetry, but start the C-angles at C90.
+    let mismatched = example_with_line(3, "2")
+        .lines()
+        .enumerate()
+        .map(|(i, l)| if i + 1 == 43 { "90" } else { l })
+        .collect::<Vec<&str>>()
+        .join("\n");
+
+    let mut ldt = EulumdatFile::new();
+    ldt.set_lenient(true);
+    match ldt.parse(&mismatched) {
+        Ok(_) => {
+            assert_eq!(*ldt.symmetry(), EulumdatSymmetry::C0C180Plane);
+            assert_eq!(ldt.infer_symmetry(), EulumdatSymmetry::NoSymmetry);
+            assert_eq!(ldt.diagnostics().len(), 1);
+            assert!(ldt.diagnostics()[0].message().contains("C0C180Plane"));
+        }
+        Err(e) => panic!("LDT lenient parse error: {}", e),
+    }
+}
+
+/// Check that a C90-C270 file listing all of its C-angles from C0 is not reported as mismatching its
+/// declared symmetry, as only the C-planes from C90 to C270 are stored.
+#[test]
+fn test_infer_symmetry_c90c270_full_c_angles() {
+    // The example has 20 C-planes of 37 intensities, of which 11 are stored for C90-C270 symmetry.
+    let lines: Vec<&str> = EXAMPLE_LDT_FILE.lines().collect();
+    let intensities_start = lines.len() - 20 * 37;
+    let file = example_with_line(3, "3")
+        .lines()
+        .take(intensities_start + 11 * 37)
+        .collect::<Vec<&str>>()
+        .join("\n");
+
+    let mut ldt = EulumdatFile::new();
+    match ldt.parse(&file) {
+        Ok(_) => {
+            assert_eq!(ldt.c_angles().len(), 20);
+            assert_eq!(ldt.c_angles()[0], 0.0);
+            assert_eq!(*ldt.symmetry(), EulumdatSymmetry::C90C270Plane);
+            assert_eq!(ldt.infer_symmetry(), EulumdatSymmetry::C90C270Plane);
+            assert!(ldt.diagnostics().is_empty());
+        }
+        Err(e) => panic!("LDT parse error: {}", e),
+    }
+}
+
+/// Check that a full set of C-planes can be trimmed to those representing a symmetry, and that
+/// expanding the trimmed set again gives back the original planes.
+#[test]
+fn test_apply_symmetry() {
+    let full_ldt = |func: fn(f64) -> f64| {
+        let mut ldt = EulumdatFile::new();
+        ldt.set_n_cplanes(36_usize);
+        ldt.set_c_angles(
+            (0..360)
+                .step_by(10)
+                .map(|ang| ang as f64)
+                .collect::<Vec<f64>>(),
+        );
+        ldt.set_g_angles(vec![0.0, 90.0]);
+        ldt.set_n_luminous_intensities_per_cplane(2_usize);
+        ldt.set_intensities(
+            ldt.c_angles()
+                .iter()
+                .flat_map(|ang| {
+                    let intens = func(ang.to_radians());
+                    [intens, 0.5 * intens]
+                })
+                .collect::<Vec<f64>>(),
+        );
+        ldt
+    };
+
+    // Symmetric about the C0-C180 plane.
+    let mut ldt = full_ldt(|c| 10.0 + c.cos());
+    let full_web: PhotometricWeb = ldt.clone().into();
+    match ldt.apply_symmetry(EulumdatSymmetry::C0C180Plane) {
+        Ok(_) => {
+            assert_eq!(ldt.n_cplanes(), 36);
+            assert_eq!(ldt.c_angles().len(), 36);
+            assert_eq!(ldt.intensities().len(), 19 * 2);
+            assert_eq!(ldt.infer_symmetry(), EulumdatSymmetry::C0C180Plane);
+            let web: PhotometricWeb = ldt.into();
+            assert!(web.approx_eq(&full_web, 1E-9));
+        }
+        Err(e) => panic!("Apply symmetry error: {}", e),
+    }
+
+    // Symmetric about the C90-C270 plane.
+    let mut ldt = full_ldt(|c| 10.0 + c.sin());
+    let full_web: PhotometricWeb = ldt.clone().into();
+    match ldt.apply_symmetry(EulumdatSymmetry::C90C270Plane) {
+        Ok(_) => {
+            assert_eq!(ldt.intensities().len(), 19 * 2);
+            let web: PhotometricWeb = ldt.into();
+            assert!(web.approx_eq(&full_web, 1E-9));
+        }
+        Err(e) => panic!("Apply symmetry error: {}", e),
+    }
+
+    // The symmetry can't be applied again, once the intensities have been trimmed.
+    let mut ldt = full_ldt(|_| 1.0);
+    ldt.apply_symmetry(EulumdatSymmetry::AboutVerticalAxis)
+        .unwrap();
+    assert_eq!(ldt.intensities().len(), 2);
+    assert!(matches!(
+        ldt.apply_symmetry(EulumdatSymmetry::C0C180Plane),
+        Err(Error::LDTError(ldt_err::Error::SymmetryAlreadyApplied(
+            EulumdatSymmetry::AboutVerticalAxis
+        )))
+    ));
+
+    // Nor can it be applied if the intensities are missing for some of the C-planes.
+    let mut ldt = full_ldt(|_| 1.0);
+    ldt.set_intensities(ldt.intensities()[..4].to_vec());
+    assert!(matches!(
+        ldt.apply_symmetry(EulumdatSymmetry::C0C180Plane),
+        Err(Error::LDTError(ldt_err::Error::IncompleteCPlanes(36, 2)))
+    ));
+}
+
+#[test]
+fn test_parse_empty_file() {
+    let mut ldt = EulumdatFile::new();
+    assert!(matches!(
+        ldt.parse(""),
+        Err(Error::LDTError(ldt_err::Error::EmptyFile))
+    ));
+    assert!(matches!(
+        ldt.parse("\n  \n"),
+        Err(Error::LDTError(ldt_err::Error::EmptyFile))
+    ));
+}
+
+/// Check the area of rectangular and circular luminous openings.
+#[test]
+fn test_luminous_area() {
+    let mut ldt = EulumdatFile::new();
+    ldt.set_luminous_area_length(1200.0);
+    ldt.set_luminous_area_width(300.0);
+    assert_relative_eq!(ldt.luminous_area(), 0.36, epsilon = 1E-12);
+    assert_relative_eq!(ldt.aspect_ratio(), 4.0, epsilon = 1E-12);
+
+    // A circular opening is stored with a width of zero, and the diameter as its length.
+    ldt.set_luminous_area_length(200.0);
+    ldt.set_luminous_area_width(0.0);
+    assert_relative_eq!(
+        ldt.luminous_area(),
+        std::f64::consts::PI * 0.01,
+        epsilon = 1E-12
+    );
+    assert_relative_eq!(ldt.aspect_ratio(), 1.0, epsilon = 1E-12);
+}
+
+/// Check that a EULUMDAT file constructed from a photometric web has self-consistent arrays,
+/// and that its header fields can be set before writing.
+#[test]
+fn test_from_photometric_web() {
+    let web = match PhotometricWeb::from_path(Path::new("./src/io/eulumdat/example.ldt")) {
+        Ok(web) => web,
+        Err(e) => panic!("Read error: {}", e),
+    };
+
+    let mut ldt = EulumdatFile::from_photometric_web(&web);
+    assert_eq!(ldt.symmetry(), &EulumdatSymmetry::NoSymmetry);
+    assert_eq!(ldt.n_cplanes(), web.n_planes());
+    assert_eq!(ldt.c_angles().len(), ldt.n_cplanes());
+    assert_eq!(
+        ldt.g_angles().len(),
+        ldt.n_luminous_intensities_per_cplane()
+    );
+    assert_eq!(
+        ldt.intensities().len(),
+        (ldt.mc2() - ldt.mc1() + 1) * ldt.n_luminous_intensities_per_cplane()
+    );
+    assert_eq!(ldt.lamp_sets().len(), 1);
+
+    ldt.set_luminaire_name("Constructed");
+    let mut reread = EulumdatFile::new();
+    match reread.parse(&ldt.to_string()) {
+        Ok(_) => {
+            assert_eq!(reread.luminaire_name(), "Constructed");
+            assert_eq!(reread.intensities(), ldt.intensities());
+        }
+        Err(e) => panic!("Parse error: {}", e),
+    }
+}
+
+/// Check that reading the example file into a web and writing it back preserves the relative intensities,
+/// the lamp flux and the light output ratio.
+#[test]
+fn test_from_photometric_web_round_trip() {
+    let mut original = EulumdatFile::new();
+    original.parse(EXAMPLE_LDT_FILE).unwrap();
+    let web = original.to_photometric_web().unwrap();
+
+    let written = EulumdatFile::from_photometric_web(&web);
+    assert_eq!(written.lamp_sets()[0].total_luminous_flux(), 5134.0);
+    assert_relative_eq!(
+        written.light_output_ratio_luminaire(),
+        original.light_output_ratio_luminaire(),
+        epsilon = 1.0
+    );
+    // The example is stored without symmetry, so the intensities are written in the same order.
+    assert_eq!(written.intensities().len(), original.intensities().len());
+    for (int_written, int) in written.intensities().iter().zip(original.intensities()) {
+        assert_relative_eq!(*int_written, *int, max_relative = 1E-12);
+    }
+
+    // Without rated lumens, the flux of the web is taken as the lamp flux.
+    let mut unrated = original.to_photometric_web().unwrap();
+    unrated.set_total_rated_lumens(None);
+    let written = EulumdatFile::from_photometric_web(&unrated);
+    assert_relative_eq!(
+        written.lamp_sets()[0].total_luminous_flux(),
+        web.total_flux(),
+        max_relative = 1E-12
+    );
+    assert_relative_eq!(
+        written.light_output_ratio_luminaire(),
+        100.0,
+        max_relative = 1E-12
+    );
+}
+
+/// Check that streaming a large, high resolution file to disk writes every line, and can be read back.
+#[test]
+fn test_write_to_large_file() {
+    let mut web = PhotometricWeb::new();
+    web.set_planes(
+        (0..360)
+            .map(|c_deg| {
+                let mut plane = Plane::new();
+                plane.set_angle_degrees(c_deg as f64);
+                plane.set_angles_degrees(&(0..181).map(|g_deg| g_deg as f64).collect::<Vec<f64>>());
+                plane.set_intensities(
+                    (0..181)
+                        .map(|g_deg| {
+                            1000.0 * (1.0 + (g_deg as f64).to_radians().cos()) + c_deg as f64
+                        })
+                        .collect::<Vec<f64>>(),
+                );
+                plane
+            })
+            .collect::<Vec<Plane>>(),
+    );
+    let file = EulumdatFile::from_photometric_web(&web);
+
+    let path = std::env::temp_dir().join("lidrs_test_ldt_write_to.ldt");
+    if let Err(e) = file.to_file(&path) {
+        panic!("Write error: {}", e);
+    }
+    // The 26 line header, a lamp set, the direct ratios and the angles, then every intensity on its own line.
+    let written = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(written.lines().count(), 26 + 6 + 10 + 360 + 181 + 360 * 181);
+
+    match EulumdatFile::parse_file(&path) {
+        Ok(reread) => {
+            assert_eq!(reread.n_cplanes(), 360);
+            assert_eq!(reread.n_luminous_intensities_per_cplane(), 181);
+            assert_eq!(reread.c_angles(), file.c_angles());
+            assert_eq!(reread.g_angles(), file.g_angles());
+            assert_eq!(reread.intensities(), file.intensities());
+        }
+        Err(e) => panic!("Read error: {}", e),
+    }
+}
+
+/// Check that a file whose sections are shorter than its counts can't be written, rather than panicking,
+/// while a file built from an empty web can.
+#[test]
+fn test_write_to_inconsistent() {
+    let mut buffer = Vec::new();
+    assert!(EulumdatFile::from_photometric_web(&PhotometricWeb::new())
+        .write_to(&mut buffer)
+        .is_ok());
+
+    let mut ldt = EulumdatFile::new();
+    ldt.parse(EXAMPLE_LDT_FILE).unwrap();
+    ldt.set_intensities(ldt.intensities()[..10].to_vec());
+    let mut buffer = Vec::new();
+    match ldt.write_to(&mut buffer) {
+        Ok(_) => panic!("Expected a write error."),
+        Err(e) => {
+            assert_eq!(e.kind(), std::io::ErrorKind::InvalidData);
+            assert!(buffer.is_empty());
+        }
+    }
+}
+
+/// Check that writing the example file is byte-identical to the output of the original per-line writer,
+/// which was captured in `example_written.ldt`.
+#[test]
+fn test_write_example_unchanged() {
+    let mut ldt = EulumdatFile::new();
+    if let Err(e) = ldt.parse(EXAMPLE_LDT_FILE) {
+        panic!("Parse error: {}", e);
+    }
+    assert_eq!(ldt.to_string(), include_str!("example_written.ldt"));
+}
+
+/// Check that the offending line is quoted when the raw lines are kept.
+#[test]
+fn test_describe_error_quotes_line() {
+    let mut lines: Vec<&str> = EXAMPLE_LDT_FILE.lines().collect();
+    lines[4] = "not-a-number";
+    let file = lines.join("\n");
+
+    let mut options = ParseOptions::new();
+    options.set_keep_raw_lines(true);
+    let mut ldt = EulumdatFile::new();
+    match ldt.parse_with_options(&file, options) {
+        Ok(_) => panic!("Expected a parse error."),
+        Err(e) => {
+            assert!(matches!(
+                e,
+                Error::LDTError(ldt_err::Error::ParseFloatError(5, _))
+            ));
+            assert_eq!(e.line(), Some(5));
+            assert!(ldt.describe_error(&e).ends_with("5 | not-a-number"));
+        }
+    }
+
+    // Without the raw lines, only the error itself is described.
+    let mut ldt = EulumdatFile::new();
+    match ldt.parse(&file) {
+        Ok(_) => panic!("Expected a parse error."),
+        Err(e) => assert_eq!(ldt.describe_error(&e), e.to_string()),
+    }
+}
+
+/// Check that the gamma angles are only offset by the measurement tilt when requested.
+#[test]
+fn test_get_planes_apply_tilt() {
+    let mut ldt = EulumdatFile::new();
+    if let Err(e) = ldt.parse(EXAMPLE_LDT_FILE) {
+        panic!("Parse error: {}", e);
+    }
+    ldt.set_tilt(10.0);
+    let untilted = ldt.get_planes().unwrap();
+    assert!(!ldt.apply_tilt());
+    assert_eq!(untilted[0].angles_deg()[0], ldt.g_angles()[0]);
+
+    ldt.set_apply_tilt(true);
+    let tilted = ldt.get_planes().unwrap();
+    assert_eq!(tilted.len(), untilted.len());
+    for (tilted_plane, plane) in tilted.iter().zip(untilted.iter()) {
+        assert_eq!(tilted_plane.intensities(), plane.intensities());
+        for (tilted_angle, angle) in tilted_plane.angles_deg().iter().zip(plane.angles_deg()) {
+            assert_relative_eq!(*tilted_angle, angle + 10.0, epsilon = 1E-9);
+        }
+    }
+}
+
+/// Check that parsing via `TryFrom` gives the same result as parsing into a new file.
+#[test]
+fn test_try_from_str() {
+    let mut ldt = EulumdatFile::new();
+    ldt.parse(EXAMPLE_LDT_FILE).unwrap();
+    match EulumdatFile::try_from(EXAMPLE_LDT_FILE) {
+        Ok(parsed) => assert_eq!(parsed, ldt),
+        Err(e) => panic!("Parse error: {}", e),
+    }
+
+    assert!(matches!(
+        EulumdatFile::try_from("not a EULUMDAT file\n1\nnot-a-number"),
+        Err(Error::LDTError(_))
+    ));
+}
+
+#[test]
+fn test_get_planes_no_gamma_angles() {
+    // A default file has no gamma angles to chunk the intensities by.
+    let ldt = EulumdatFile::new();
+    assert!(matches!(
+        ldt.get_planes(),
+        Err(ldt_err::Error::NoGammaAngles)
+    ));
+    assert!(matches!(
+        ldt.to_photometric_web(),
+        Err(ldt_err::Error::NoGammaAngles)
+    ));
+    let web: PhotometricWeb = ldt.into();
+    assert_eq!(web.n_planes(), 0);
+}
+
+/// Check that the relative intensities are scaled by the lamp flux into candela, so that the light output ratio
+/// of the example luminaire matches the 100% given in its header.
+#[test]
+fn test_get_planes_lamp_flux() {
+    let mut ldt = EulumdatFile::new();
+    ldt.parse(EXAMPLE_LDT_FILE).unwrap();
+    assert_eq!(ldt.light_output_ratio_luminaire(), 100.0);
+    let web: PhotometricWeb = ldt.clone().into();
+    assert!(web
+        .planes()
+        .iter()
+        .all(|pl| *pl.units() == IntensityUnits::Candela));
+    assert_relative_eq!(web.light_output_ratio().unwrap(), 1.0, epsilon = 1E-2);
+    assert_relative_eq!(
+        web.total_flux_lumens().unwrap(),
+        5134.0,
+        max_relative = 1E-2
+    );
+
+    // Without any lamp sets the intensities can only be left relative to 1000 lm.
+    ldt.set_lamp_sets(Vec::new());
+    let relative: PhotometricWeb = ldt.into();
+    assert!(relative
+        .planes()
+        .iter()
+        .all(|pl| *pl.units() == IntensityUnits::CandelaPerKilolumen));
+    for (pl_rel, pl) in relative.planes().iter().zip(web.planes()) {
+        for (int_rel, int) in pl_rel.intensities().iter().zip(pl.intensities()) {
+            assert_relative_eq!(*int_rel * 5.134, *int, max_relative = 1E-12);
+        }
+    }
+}
+
+#[test]
+fn test_get_planes_conversion_factor() {
+    // A default file leaves the intensities unscaled, in the same way as a new one.
+    assert_eq!(EulumdatFile::default(), EulumdatFile::new());
+    assert_eq!(
+        EulumdatFile::default().luminous_intensity_conversion_factor(),
+        1.0
+    );
+
+    let mut ldt = EulumdatFile::new();
+    ldt.parse(EXAMPLE_LDT_FILE).unwrap();
+    assert_eq!(ldt.luminous_intensity_conversion_factor(), 1.0);
+    let unscaled: PhotometricWeb = ldt.clone().into();
+
+    ldt.set_luminous_intensity_conversion_factor(2.5);
+    let scaled: PhotometricWeb = ldt.into();
+    for (pl_scaled, pl) in scaled.planes().iter().zip(unscaled.planes()) {
+        for (int_scaled, int) in pl_scaled.intensities().iter().zip(pl.intensities()) {
+            assert_relative_eq!(*int_scaled, 2.5 * int);
+        }
+    }
+    assert_relative_eq!(
+        scaled.total_intensity(),
+        2.5 * unscaled.total_intensity(),
+        max_relative = 1E-12
+    );
+}
+
+#[test]
+fn test_parse_bom_and_crlf() {
+    let mut clean = EulumdatFile::new();
+    clean.parse(EXAMPLE_LDT_FILE).unwrap();
+
+    let windows = format!("\u{feff}{}", EXAMPLE_LDT_FILE.replace('\n', "\r\n"));
+    let mut ldt = EulumdatFile::new();
+    ldt.parse(&windows).unwrap();
+
+    assert_eq!(ldt, clean);
+}
+
+/// Check that the heights of the luminous area of a linear luminaire are passed on to the web by direction.
+#[test]
+fn test_linear_luminaire_area_heights() {
+    let mut lines: Vec<&str> = EXAMPLE_LDT_FILE.lines().collect();
+    lines[17..21].copy_from_slice(&["30", "60", "40", "80"]);
+    let mut ldt = EulumdatFile::new();
+    ldt.parse(&lines.join("\n")).unwrap();
+    assert_eq!(*ldt.ltype(), EulumdatType::LinearLumminaire);
+
+    let web = ldt.to_photometric_web().unwrap();
+    let expected = [0.03, 0.06, 0.04, 0.08];
+    for (c_angle_deg, height) in [0.0, 90.0, 180.0, 270.0].into_iter().zip(expected) {
+        assert_relative_eq!(
+            web.luminous_area_height_at(degrees_to_radians(c_angle_deg)).unwrap(),
+            height,
+            epsilon = 1E-12
+        );
+    }
+    // Each plane can look up the height of the luminous area in its own direction.
+    assert!(web
+        .planes()
+        .iter()
+        .all(|pl| web.luminous_area_height_at(pl.angle()).is_some()));
+
+    // The example has no heights, so none are given to the web.
+    let mut ldt = EulumdatFile::new();
+    ldt.parse(EXAMPLE_LDT_FILE).unwrap();
+    assert!(ldt
+        .to_photometric_web()
+        .unwrap()
+        .luminous_area_heights()
+        .is_none());
+}
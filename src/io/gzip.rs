@@ -0,0 +1,63 @@
+//! Transparent decompression of gzip-compressed input files, enabled by the `gzip` feature.
+
+use crate::err::Error;
+use flate2::read::GzDecoder;
+use std::{
+    fs::File,
+    io::{BufReader, Read},
+    path::{Path, PathBuf},
+};
+
+/// The magic bytes at the start of every gzip stream.
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Reads and decompresses the file at `path` if it starts with the gzip magic bytes, returning `None`
+/// for files that are not compressed so that they can be read as normal. Only the magic bytes are read
+/// from an uncompressed file, and files that can't be opened are also left to the reader, which reports
+/// its own error.
+pub(crate) fn read_if_gzipped(path: &Path) -> Result<Option<String>, Error> {
+    let mut reader = match File::open(path) {
+        Ok(file) => BufReader::new(file),
+        Err(_) => return Ok(None),
+    };
+    let mut magic = Vec::with_capacity(GZIP_MAGIC.len());
+    reader
+        .by_ref()
+        .take(GZIP_MAGIC.len() as u64)
+        .read_to_end(&mut magic)
+        .map_err(|err| Error::file_error(path, err))?;
+    if magic != GZIP_MAGIC {
+        return Ok(None);
+    }
+
+    let mut contents = String::new();
+    GzDecoder::new(magic.chain(reader))
+        .read_to_string(&mut contents)
+        .map_err(|err| Error::file_error(path, err))?;
+    Ok(Some(contents))
+}
+
+/// Returns the path of the file inside a compressed file, stripping a trailing `.gz` extension,
+/// so that `lamp.ies.gz` becomes `lamp.ies`. Paths without a `.gz` extension are returned unchanged.
+pub(crate) fn inner_path(path: &Path) -> PathBuf {
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some(ext) if ext.eq_ignore_ascii_case("gz") => path.with_extension(""),
+        _ => path.to_path_buf(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::inner_path;
+    use std::path::Path;
+
+    #[test]
+    fn test_inner_path() {
+        assert_eq!(inner_path(Path::new("lamp.ies.gz")), Path::new("lamp.ies"));
+        assert_eq!(
+            inner_path(Path::new("dir/LAMP.LDT.GZ")),
+            Path::new("dir/LAMP.LDT")
+        );
+        assert_eq!(inner_path(Path::new("lamp.ies")), Path::new("lamp.ies"));
+    }
+}
@@ -17,6 +17,31 @@ pub enum Error {
     UnexpectedEndOfFile(usize),
     UnexpectedIitem(usize, usize, usize),
     FromPrimitiveError(usize, Rc<dyn std::error::Error>),
+    NoVerticalAngles,
+}
+
+impl Error {
+    /// Returns the line number that the error occurred on, if the error relates to a single line.
+    pub fn line(&self) -> Option<usize> {
+        match self {
+            Error::TiltFiltTooLong(iline)
+            | Error::InvalidKeyword(iline)
+            | Error::ParseFloatError(iline, _, _)
+            | Error::ParseIntError(iline, _, _)
+            | Error::InvalidUnit(iline)
+            | Error::ArrayIncorrectLength(iline, _, _)
+            | Error::VerticalAnglesInvalid(iline)
+            | Error::HorizontalAnglesInvalid(iline)
+            | Error::UnexpectedEndOfFile(iline)
+            | Error::UnexpectedIitem(iline, _, _)
+            | Error::FromPrimitiveError(iline, _) => Some(*iline),
+            Error::EmptyFile
+            | Error::TiltNotDefined
+            | Error::TiltFileNotFound(_)
+            | Error::TileFileIOError(_)
+            | Error::NoVerticalAngles => None,
+        }
+    }
 }
 
 impl Display for Error {
@@ -27,16 +52,16 @@ impl Display for Error {
                     format!("Tilt file IO Error: {}", err)
                 }
                 Error::EmptyFile => {
-                    format!("The file contains no lines. ")
+                    "The file contains no lines. ".to_string()
                 }
                 Error::TiltNotDefined => {
-                    format!("Unable to find TILT definition in file. ")
+                    "Unable to find TILT definition in file. ".to_string()
                 }
                 Error::TiltFileNotFound(ref path) => {
                     format!("Unable to load TILT file: {}", path)
                 }
                 Error::TiltFiltTooLong(ref len) => {
-                    format!("Tilt should be 4 lines. Reached {} lines. ", len)
+                    format!("Unexpected data after the end of the tilt section on line {}. ", len)
                 }
                 Error::InvalidKeyword(ref iline) => {
                     format!("Line {}: Invalid keyword. ", iline)
@@ -63,7 +88,7 @@ impl Display for Error {
                 }
                 Error::ArrayIncorrectLength(ref iline, ref expected, ref found) => {
                     format!(
-                        "Line {}: Array too short. Expected {}, but found {}. ",
+                        "Line {}: Array has the wrong length. Expected {}, but found {}. ",
                         iline, expected, found
                     )
                 }
@@ -73,6 +98,10 @@ impl Display for Error {
                 Error::HorizontalAnglesInvalid(ref iline) => {
                     format!("Line {}: Horizontal angles are invalid and do not conform to an allowed lateral symmetry. ", iline )
                 }
+                Error::NoVerticalAngles => {
+                    "The file has no vertical angles to divide the candela values into planes. "
+                        .to_string()
+                }
                 Error::UnexpectedEndOfFile(ref iline) => {
                     format!("Line {}: Unexpected end of file. ", iline)
                 }
@@ -95,3 +124,15 @@ impl From<std::io::Error> for Error {
         Error::TileFileIOError(Rc::new(err))
     }
 }
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::TileFileIOError(ref err) => Some(err.as_ref()),
+            Error::ParseFloatError(_, _, ref err) => Some(err),
+            Error::ParseIntError(_, _, ref err) => Some(err),
+            Error::FromPrimitiveError(_, ref err) => Some(err.as_ref()),
+            _ => None,
+        }
+    }
+}
@@ -1,10 +1,19 @@
 use super::err as ies_err;
 use super::lum_opening::IesLuminousOpening;
-use super::{phot_type::IesPhotometryType, standard::IesStandard, tilt::Tilt};
+use super::{
+    phot_type::IesPhotometryType,
+    standard::IesStandard,
+    tilt::{Tilt, TiltRef},
+};
 use crate::photweb::{Plane, mirror_first_quadrant, mirror_first_hemisphere};
+use crate::util::geom::round_angle;
 use crate::{
     err::Error,
-    photweb::{IntensityUnits, PhotometricWeb, PhotometricWebReader, PlaneOrientation},
+    io::{Diagnostics, ParseOptions},
+    photweb::{
+        IntensityUnits, PhotometricWeb, PhotometricWebReader, PhotometricWebWriter,
+        PlaneOrientation,
+    },
 };
 use property::Property;
 use regex::Regex;
@@ -12,26 +21,31 @@ use std::{
     collections::HashMap,
     default::Default,
     fs::File,
-    io::{BufReader, Read, Write},
-    path::Path,
+    io::{BufReader, BufWriter, Read, Write},
+    path::{Path, PathBuf},
     rc::Rc,
     f64::consts::{PI}
 };
 
 pub const DELIMITERS_PATTERN: &str = "[ ]+|,|[\r\n]";
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+/// The number of decimal places angles are rounded to when converting from a photometric web.
+const WRITE_ANGLE_DECIMALS: i32 = 6;
+/// The number of metres in a foot, used to convert luminous opening dimensions.
+const FEET_TO_METRES: f64 = 0.3048;
+/// The tolerance (in radians) within which a final horizontal angle is considered to close the circle at 360 degrees.
+const CLOSING_PLANE_TOLERANCE: f64 = 1E-6;
+
+/// The result of parsing a single keyword line into a (keyword, value) pair.
+type KeywordResult = Result<(usize, String, String), ies_err::Error>;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub enum LuminousOpeningUnits {
     Feet = 1,
+    #[default]
     Meters = 2,
 }
 
-impl Default for LuminousOpeningUnits {
-    fn default() -> Self {
-        LuminousOpeningUnits::Meters
-    }
-}
-
 impl From<usize> for LuminousOpeningUnits {
     fn from(val: usize) -> Self {
         match val {
@@ -56,11 +70,12 @@ impl std::fmt::Display for LuminousOpeningUnits {
 }
 
 #[allow(dead_code)]
-#[derive(Default, Clone, Debug, Property)]
+#[derive(Clone, Debug, Property)]
 pub struct IesFile {
     standard: IesStandard,
+    #[property(get(public), set(public), mut(public))]
     keywords: HashMap<String, String>,
-    tilt: Option<Tilt>,
+    tilt: TiltRef,
 
     // First line of parameters
     n_lamps: usize,
@@ -75,7 +90,13 @@ pub struct IesFile {
     luminous_opening_height: f64,
 
     // Second line of parameters.
+    #[property(get(public), set(public))]
     ballast_factor: f64,
+    /// The ballast-lamp photometric factor of LM-63-1991 and 1995, which is marked for future use
+    /// in LM-63-2002, where it is normally 1.
+    #[property(get(public), set(public))]
+    ballast_lamp_photometric_factor: f64,
+    #[property(get(public), set(public))]
     input_watts: f64,
 
     // Angles
@@ -84,6 +105,15 @@ pub struct IesFile {
 
     // Brightness vaulues, measured in candellas.
     candela_values: Vec<f64>,
+
+    // The options used when parsing the file.
+    options: ParseOptions,
+    // Non-fatal issues found when parsing the file.
+    #[property(get(public), set(disable))]
+    diagnostics: Diagnostics,
+    // The raw text of each line of the parsed file, kept when requested by the parse options.
+    #[property(get(public), set(disable))]
+    raw_lines: Vec<String>,
 }
 
 impl IesFile {
@@ -94,43 +124,135 @@ impl IesFile {
         }
     }
 
+    /// Builds an IES file from a photometric web, using Type C photometry with a C-plane for each
+    /// plane in the web. The vertical angles are taken from the first plane, so the planes are assumed
+    /// to share the same gamma angles. If the rated lumens are unknown, absolute photometry is used.
+    /// The remaining fields, such as the keywords, are left as their defaults so that they can be set
+    /// before the file is written with `to_file`.
+    pub fn from_photometric_web(photweb: &PhotometricWeb) -> IesFile {
+        let vertical_angles = match photweb.planes().first() {
+            Some(plane) => plane
+                .angles_deg()
+                .iter()
+                .map(|ang| round_angle(*ang, WRITE_ANGLE_DECIMALS))
+                .collect(),
+            None => Vec::new(),
+        };
+        let horizontal_angles: Vec<f64> = photweb
+            .planes()
+            .iter()
+            .map(|pl| round_angle(pl.angle_deg(), WRITE_ANGLE_DECIMALS))
+            .collect();
+
+        IesFile {
+            standard: IesStandard::Iesna2002,
+            n_lamps: 1,
+            lumens_per_lamp: photweb.total_rated_lumens().unwrap_or(-1.0),
+            candela_multiplying_factor: 1.0,
+            n_vertical_angles: vertical_angles.len(),
+            n_horizontal_angles: horizontal_angles.len(),
+            photometric_type: IesPhotometryType::TypeC,
+            ballast_factor: 1.0,
+            ballast_lamp_photometric_factor: 1.0,
+            input_watts: photweb.input_watts().unwrap_or(0.0),
+            vertical_angles,
+            horizontal_angles,
+            candela_values: photweb
+                .planes()
+                .iter()
+                .flat_map(|pl| pl.intensities().iter().copied())
+                .collect(),
+            ..Default::default()
+        }
+    }
+
     /// A wrapper around the parsing code, that opens a file and reads it.
     pub fn parse_file(filepath: &Path) -> Result<IesFile, Error> {
-        let infile = File::open(filepath)?;
+        let infile = File::open(filepath).map_err(|err| Error::file_error(filepath, err))?;
         let mut ies_string_buf = String::new();
-        BufReader::new(infile).read_to_string(&mut ies_string_buf)?;
+        BufReader::new(infile)
+            .read_to_string(&mut ies_string_buf)
+            .map_err(|err| Error::file_error(filepath, err))?;
         let mut ies_file = IesFile::new();
         ies_file.parse(&ies_string_buf)?;
         Ok(ies_file)
     }
 
-    /// Attempts to parse an input file.
-    pub fn parse(&mut self, ies_string: &String) -> Result<(), Error> {
-        let standard = match ies_string.lines().into_iter().nth(0) {
+    /// Sets whether the parser should be lenient, skipping malformed keyword lines and missing
+    /// candela values and recording a warning instead of returning an error.
+    ///
+    /// ```
+    /// use lidrs::io::ies::IesFile;
+    ///
+    /// // Insert a line that is not a keyword into the keyword block.
+    /// let file = std::fs::read_to_string("src/io/ies/iesna2002_example_typec.ies")
+    ///     .unwrap()
+    ///     .replacen("[TESTLAB]", "Not a keyword\n[TESTLAB]", 1);
+    ///
+    /// let mut ies = IesFile::new();
+    /// ies.set_lenient(true);
+    /// ies.parse(&file).unwrap();
+    /// assert_eq!(ies.diagnostics().len(), 1);
+    /// assert_eq!(ies.diagnostics()[0].line(), Some(3));
+    /// ```
+    pub fn set_lenient(&mut self, lenient: bool) {
+        self.options.set_strict(!lenient);
+    }
+
+    /// Attempts to parse an input file, using the current parse options (strict by default).
+    pub fn parse(&mut self, ies_string: &str) -> Result<(), Error> {
+        self.parse_with_options(ies_string, self.options.clone())
+    }
+
+    /// Attempts to parse an input file, using the provided parse options.
+    pub fn parse_with_options(
+        &mut self,
+        ies_string: &str,
+        options: ParseOptions,
+    ) -> Result<(), Error> {
+        let ies_string = &crate::io::normalise_text(ies_string);
+        self.raw_lines = if options.keep_raw_lines() {
+            ies_string.lines().map(String::from).collect()
+        } else {
+            Vec::new()
+        };
+        self.options = options;
+        self.diagnostics.clear();
+
+        let standard = match ies_string.lines().nth(0) {
             None => Err(Error::IESError(ies_err::Error::EmptyFile)),
             Some(val) => Ok(IesStandard::from(val)),
         };
 
         // If at this point we have an error, just return it. Else we can continue.
-        if standard.is_err() {
-            return Err(standard.unwrap_err());
-        }
-        self.standard = standard.unwrap();
+        self.standard = standard?;
 
         // Parse the keywords.
-        self.parse_keywords(&ies_string)?;
+        self.parse_keywords(ies_string)?;
 
         // Parse the TILT.
-        self.parse_tilt(&ies_string)?;
+        self.parse_tilt(ies_string)?;
 
         // Now get he remaining values.
-        self.parse_properties(&ies_string)?;
+        self.parse_properties(ies_string)?;
 
         Ok(())
     }
 
+    /// Describes an error returned when parsing this file. If the raw lines were kept when parsing,
+    /// as set by `ParseOptions::set_keep_raw_lines`, the text of the line the error occurred on is quoted.
+    pub fn describe_error(&self, err: &Error) -> String {
+        // Line numbers are counted from one.
+        match err.line() {
+            Some(iline) if iline >= 1 && iline <= self.raw_lines.len() => {
+                format!("{}\n{} | {}", err, iline, self.raw_lines[iline - 1])
+            }
+            _ => err.to_string(),
+        }
+    }
+
     /// Parses the keywords section of the file.
-    pub fn parse_keywords(&mut self, ies_string: &String) -> Result<(), ies_err::Error> {
+    pub fn parse_keywords(&mut self, ies_string: &str) -> Result<(), ies_err::Error> {
         // First we find the start line, if not 1986 standard, this will be after the first line.
         let start = if self.standard == IesStandard::Iesna1986 {
             0
@@ -149,15 +271,21 @@ impl IesFile {
         let kw_regex = Regex::new("\\[([A-Z_]+)\\] (.*)").unwrap();
 
         // Get those lines and iterate through them.
-        let (keywords, errors): (
-            Vec<Result<(String, String), ies_err::Error>>,
-            Vec<Result<(String, String), ies_err::Error>>,
-        ) = ies_string
+        // When not being strict, malformed lines are skipped and recorded as diagnostics.
+        let strict = self.options.strict();
+        let mut skipped_lines: Vec<usize> = Vec::new();
+        let (keywords, errors): (Vec<KeywordResult>, Vec<KeywordResult>) = ies_string
             .lines()
-            .into_iter()
             .enumerate()
             .skip(start)
             .take(end.unwrap() - start)
+            .filter(|(iline, line)| {
+                let keep = strict || kw_regex.is_match(line);
+                if !keep {
+                    skipped_lines.push(iline + 1);
+                }
+                keep
+            })
             .map(|(iline, line)| {
                 // Get the keyword.
                 let cap = kw_regex.captures_iter(line);
@@ -166,6 +294,7 @@ impl IesFile {
                     Some(kw) => {
                         // We have a keyword - data pair.
                         Ok((
+                            iline + 1,
                             kw.get(1).unwrap().as_str().to_owned(),
                             kw.get(2).unwrap().as_str().to_owned(),
                         ))
@@ -174,20 +303,28 @@ impl IesFile {
             })
             .partition(Result::is_ok);
 
+        for iline in skipped_lines {
+            self.diagnostics.push(Some(iline), "Skipped malformed keyword line.");
+        }
+
         let mut previous_kw: Option<String> = None;
         match errors.first() {
             None => {
                 for vals in keywords {
-                    let kw = vals.unwrap();
+                    let (iline, kw, value) = vals.unwrap();
 
-                    if kw.0 == "MORE" {
-                        self.keywords
-                            .get_mut(previous_kw.as_ref().unwrap())
-                            .unwrap()
-                            .push_str(&format!(" {}", kw.1));
+                    if kw == "MORE" {
+                        // A continuation must follow the keyword it extends.
+                        match previous_kw
+                            .as_ref()
+                            .and_then(|prev| self.keywords.get_mut(prev))
+                        {
+                            Some(prev_value) => prev_value.push_str(&format!(" {}", value)),
+                            None => return Err(ies_err::Error::InvalidKeyword(iline)),
+                        }
                     } else {
-                        previous_kw = Some(kw.0.clone());
-                        self.keywords.insert(kw.0, kw.1);
+                        previous_kw = Some(kw.clone());
+                        self.keywords.insert(kw, value);
                     }
                 }
                 Ok(())
@@ -196,7 +333,7 @@ impl IesFile {
         }
     }
 
-    pub fn parse_tilt(&mut self, ies_string: &String) -> Result<(), ies_err::Error> {
+    pub fn parse_tilt(&mut self, ies_string: &str) -> Result<(), ies_err::Error> {
         let tilt_res = match ies_string
             .lines()
             .position(|line| line.starts_with("TILT="))
@@ -210,21 +347,16 @@ impl IesFile {
                     .replace("TILT=", "")
                     .as_str()
                 {
-                    "NONE" => Ok(None),
+                    "NONE" => Ok(TiltRef::None),
                     "INCLUDE" => {
-                        // Pick off just the 4 lines we're interested in and parse.
-                        let tilt_lines = ies_string
-                            .lines()
-                            .skip(val + 1)
-                            .take(4)
-                            .fold("".to_string(), |accum, item| format!("{}{}\n", accum, item));
-                        Tilt::parse(tilt_lines.as_str())
-                    }
-                    // In this case, we are being given a filename.
-                    _ => {
-                        let tilt_line = ies_string.lines().nth(val).unwrap();
-                        Tilt::from_file(&Path::new(&tilt_line.replace("TILT=", "").to_owned()))
+                        // Parse the tilt data from the lines that follow, which may span more than 4 lines.
+                        // These start on the (1-based) line after the TILT line.
+                        let tilt_lines = ies_string.lines().skip(val + 1).collect::<Vec<&str>>();
+                        Tilt::parse_lines(&tilt_lines.join("\n"), val + 2)
+                            .map(|(tilt, _)| TiltRef::Include(tilt))
                     }
+                    // In this case, we are being given a filename, which we keep a reference to.
+                    filename => Ok(TiltRef::File(PathBuf::from(filename))),
                 }
             }
         };
@@ -239,7 +371,7 @@ impl IesFile {
     }
 
     /// This function reads the properties from the file into the data structure.
-    pub fn parse_properties(&mut self, ies_string: &String) -> Result<(), ies_err::Error> {
+    pub fn parse_properties(&mut self, ies_string: &str) -> Result<(), ies_err::Error> {
         // I will likely revisit this in the future as I'm unhappy with how this is implemented.
         // It is implemented in a really awkward way. I would like to do this in a nicer way, but
         // I am in a rush and I need it to be working.
@@ -253,6 +385,7 @@ impl IesFile {
             return Err(ies_err::Error::TiltNotDefined);
         };
 
+        // Skip the TILT line, along with the tilt data if it is included in the file.
         let tilt_skip = match ies_string
             .lines()
             .nth(tilt_end_res.unwrap())
@@ -260,7 +393,15 @@ impl IesFile {
             .replace("TILT=", "")
             .as_str()
         {
-            "INCLUDE" => 5,
+            "INCLUDE" => {
+                let tilt_lines = ies_string
+                    .lines()
+                    .skip(tilt_end_res.unwrap() + 1)
+                    .collect::<Vec<&str>>();
+                let (_, n_tilt_lines) =
+                    Tilt::parse_lines(&tilt_lines.join("\n"), tilt_end_res.unwrap() + 2)?;
+                1 + n_tilt_lines
+            }
             _ => 1,
         };
 
@@ -270,14 +411,15 @@ impl IesFile {
             .lines()
             .skip(start_line)
             .enumerate()
-            .map(|(iline, str)| {
+            .flat_map(|(iline, str)| {
+                // Drop any empty tokens from repeated or trailing delimiters, so they don't shift the item indices.
                 let tmp: Vec<(usize, String)> = split_regex
                     .split(str.trim())
+                    .filter(|val_str| !val_str.is_empty())
                     .map(|val_str| (start_line + iline + 1, String::from(val_str)))
                     .collect();
                 tmp
             })
-            .flatten()
             .collect();
 
         let errs: Vec<ies_err::Error> = lines
@@ -401,7 +543,17 @@ impl IesFile {
                             err,
                         )),
                     },
-                    11 => Ok(()),
+                    11 => match item.parse() {
+                        Ok(val) => {
+                            self.ballast_lamp_photometric_factor = val;
+                            Ok(())
+                        }
+                        Err(err) => Err(ies_err::Error::ParseFloatError(
+                            *iline,
+                            Some(iitem + 1),
+                            err,
+                        )),
+                    },
                     12 => match item.parse() {
                         Ok(val) => {
                             self.input_watts = val;
@@ -459,23 +611,39 @@ impl IesFile {
                     _ => Err(ies_err::Error::UnexpectedIitem(
                         *iline,
                         12 + self.n_vertical_angles + self.n_horizontal_angles,
-                        lines.iter().count(),
+                        lines.len(),
                     )),
                 }
             })
-            .filter_map(|res| {
-                if res.is_err() {
-                    Some(res.unwrap_err())
-                } else {
-                    None
-                }
-            })
+            .filter_map(Result::err)
             .collect();
 
         if !errs.is_empty() {
             return Err(errs.first().unwrap().clone());
         }
 
+        // Check that the candela values fill the grid of angles exactly, as otherwise the planes would be malformed.
+        // When not being strict, the mismatch is recorded as a diagnostic instead.
+        let n_expected = self.n_vertical_angles * self.n_horizontal_angles;
+        if self.candela_values.len() != n_expected {
+            let last_line = lines.last().map_or(start_line, |(iline, _)| *iline);
+            if self.options.strict() {
+                return Err(ies_err::Error::ArrayIncorrectLength(
+                    last_line,
+                    n_expected,
+                    self.candela_values.len(),
+                ));
+            }
+            self.diagnostics.push(
+                Some(last_line),
+                &format!(
+                    "Expected {} candela values, but found {}.",
+                    n_expected,
+                    self.candela_values.len()
+                ),
+            );
+        }
+
         Ok(())
     }
 
@@ -484,22 +652,16 @@ impl IesFile {
     /// - Completely in the bottom hemisphere: first angles and 0 degrees and 90 degress respectively.
     /// - Completely in the top hemisphere: first angles and 90 degrees and 180 degress respectively.
     /// - Otherwise: first angles and 0 degrees and 180 degress respectively.
-    pub fn vertical_angles_valid(angles: &Vec<f64>) -> bool {
+    pub fn vertical_angles_valid(angles: &[f64]) -> bool {
         match angles.first() {
             Some(first) => match first {
                 x if *x == 0.0 => match angles.last() {
                     None => false,
-                    Some(last) => match last {
-                        y if *y == 90.0 || *y == 180.0 => true,
-                        _ => false,
-                    },
+                    Some(last) => *last == 90.0 || *last == 180.0,
                 },
                 x if *x == 90.0 => match angles.last() {
                     None => false,
-                    Some(last) => match last {
-                        y if *y == 180.0 => true,
-                        _ => false,
-                    },
+                    Some(last) => *last == 180.0,
                 },
                 _ => false,
             },
@@ -515,7 +677,7 @@ impl IesFile {
     /// - If the last value is 180.0 degress, the distribution is symmetric about a vertical plane.
     /// - If the last value is greater than 180.0 and less than or equal to 360.0, no lateral symmetries.
     /// - Hence, the valid last values are: 0.0, 90.0, 180.0 - 360.0.  
-    pub fn horizontal_angles_valid(angles: &Vec<f64>) -> bool {
+    pub fn horizontal_angles_valid(angles: &[f64]) -> bool {
         match angles.first() {
             Some(first) => match first {
                 x if *x == 0.0 => match angles.last() {
@@ -533,14 +695,91 @@ impl IesFile {
         }
     }
 
-    /// Writes the currently loaded EULUMDAT file to a specified file.
-    /// The written value is determined by `LdtFile::to_string(&self)`.
+    /// Writes the currently loaded IES file to a specified file.
+    /// The file is streamed through a buffered writer, as described by `IesFile::write_to`.
     pub fn to_file(&self, outpath: &Path) -> Result<(), Error> {
-        let mut file = File::create(outpath)?;
-        file.write(self.to_string().as_bytes())?;
+        let mut writer = BufWriter::new(File::create(outpath)?);
+        self.write_to(&mut writer)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Writes the IES file line by line to the given writer, without building the whole file in memory.
+    /// This produces the same output as `IesFile::to_string(&self)`.
+    /// Returns an `InvalidData` error, before writing anything, if the angles and candela values don't match
+    /// the number of vertical and horizontal angles.
+    pub fn write_to<W: Write>(&self, w: &mut W) -> std::io::Result<()> {
+        // Check that the arrays are consistent with the number of angles.
+        if self.vertical_angles.len() != self.n_vertical_angles
+            || self.horizontal_angles.len() != self.n_horizontal_angles
+            || self.candela_values.len() != self.n_vertical_angles * self.n_horizontal_angles
+        {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!(
+                    "Expected {} vertical angles, {} horizontal angles and {} candela values, but found {}, {} and {}.",
+                    self.n_vertical_angles,
+                    self.n_horizontal_angles,
+                    self.n_vertical_angles * self.n_horizontal_angles,
+                    self.vertical_angles.len(),
+                    self.horizontal_angles.len(),
+                    self.candela_values.len()
+                ),
+            ));
+        }
+
+        // Get the standard header.
+        let stan = self.standard.to_string();
+        if !stan.is_empty() {
+            writeln!(w, "{}", stan)?;
+        }
+
+        // Output keywords
+        for (key, val) in self.keywords.iter() {
+            writeln!(w, "[{}] {}", key, val)?;
+        }
+
+        // Output the tilt.
+        write!(w, "{}", self.tilt)?;
+
+        // Now output the parameters and arrays.
+        writeln!(
+            w,
+            "{} {} {} {} {} {} {} {} {} {}",
+            self.n_lamps,
+            self.lumens_per_lamp,
+            self.candela_multiplying_factor,
+            self.n_vertical_angles,
+            self.n_horizontal_angles,
+            (self.photometric_type.clone() as usize),
+            self.luminous_opening_units,
+            self.luminous_opening_width,
+            self.luminous_opening_length,
+            self.luminous_opening_height
+        )?;
+        writeln!(
+            w,
+            "{} {} {}",
+            self.ballast_factor, self.ballast_lamp_photometric_factor, self.input_watts
+        )?;
+        Self::write_values(w, &self.vertical_angles)?;
+        Self::write_values(w, &self.horizontal_angles)?;
+        // A file without any vertical angles has no candela values to write.
+        for values in self.candela_values.chunks(self.n_vertical_angles.max(1)) {
+            Self::write_values(w, values)?;
+        }
+
         Ok(())
     }
 
+    /// Writes a single line of space-separated values to the given writer.
+    fn write_values<W: Write>(w: &mut W, values: &[f64]) -> std::io::Result<()> {
+        for val in values {
+            write!(w, "{} ", val)?;
+        }
+        writeln!(w)
+    }
+
     /// Outputs the keywords in the file to a string.
     pub fn keywords_to_string(&self) -> String {
         self.keywords
@@ -550,6 +789,38 @@ impl IesFile {
             })
     }
 
+    /// Gets the value of the given keyword, without surrounding whitespace.
+    fn keyword(&self, key: &str) -> Option<&str> {
+        self.keywords.get(key).map(|val| val.trim())
+    }
+
+    /// The manufacturer of the luminaire, from the `[MANUFAC]` keyword.
+    pub fn manufacturer(&self) -> Option<&str> {
+        self.keyword("MANUFAC")
+    }
+
+    /// The laboratory that performed the test, from the `[TESTLAB]` keyword.
+    pub fn test_lab(&self) -> Option<&str> {
+        self.keyword("TESTLAB")
+    }
+
+    /// The catalogue number of the lamp, from the `[LAMPCAT]` keyword.
+    pub fn lamp_catalog(&self) -> Option<&str> {
+        self.keyword("LAMPCAT")
+    }
+
+    /// The date the file was issued, from the `[ISSUEDATE]` keyword.
+    pub fn issue_date(&self) -> Option<&str> {
+        self.keyword("ISSUEDATE")
+    }
+
+    /// Whether the file uses absolute photometry, marked by a negative (normally -1) number of lumens per lamp.
+    /// The candela values of an absolutely measured luminaire are not relative to any rated lamp flux, so no
+    /// rated lumens are passed on to the photometric web, and its light output ratio is not computed.
+    pub fn is_absolute_photometry(&self) -> bool {
+        self.lumens_per_lamp < 0.0
+    }
+
     /// Get the type and properties of the luminous opening.
     pub fn get_luminous_opening(&self) -> IesLuminousOpening {
         IesLuminousOpening::from_dimensions(
@@ -559,8 +830,36 @@ impl IesFile {
         )
     }
 
+    /// Converts the file into a photometric web, returning an error if the planes cannot be built.
+    pub fn to_photometric_web(&self) -> Result<PhotometricWeb, ies_err::Error> {
+        let mut photweb = PhotometricWeb::new();
+        photweb.set_planes(self.get_planes()?);
+        if self.input_watts > 0.0 {
+            photweb.set_input_watts(self.input_watts);
+        }
+        // Absolute photometry has no rated flux, and a relative file needs a positive one to be useful.
+        if !self.is_absolute_photometry() && self.lumens_per_lamp > 0.0 {
+            photweb.set_total_rated_lumens(self.n_lamps as f64 * self.lumens_per_lamp);
+        }
+        // The web stores the luminous opening in metres, and a point source has no opening.
+        let to_metres = match self.luminous_opening_units {
+            LuminousOpeningUnits::Feet => FEET_TO_METRES,
+            LuminousOpeningUnits::Meters => 1.0,
+        };
+        let opening = IesLuminousOpening::from_dimensions(
+            self.luminous_opening_width * to_metres,
+            self.luminous_opening_length * to_metres,
+            self.luminous_opening_height * to_metres,
+        );
+        if opening != IesLuminousOpening::Point {
+            photweb.set_luminous_opening(opening);
+        }
+        Ok(photweb)
+    }
+
     /// Gets the planes from this object.
-    pub fn get_planes(&self) -> Vec<Plane> {
+    /// Returns an error if there are no vertical angles to divide the candela values into planes.
+    pub fn get_planes(&self) -> Result<Vec<Plane>, ies_err::Error> {
         match self.photometric_type {
             IesPhotometryType::TypeA => self.get_planes_type_a(),
             IesPhotometryType::TypeB => self.get_planes_type_b(),
@@ -569,33 +868,53 @@ impl IesFile {
     }
 
     /// Get the planes from a Type A photometry IES file.
-    pub fn get_planes_type_a(&self) -> Vec<Plane> {
+    pub fn get_planes_type_a(&self) -> Result<Vec<Plane>, ies_err::Error> {
         todo!()
     }
 
     /// Get the planes from a Type A photometry IES file.
-    pub fn get_planes_type_b(&self) -> Vec<Plane> {
+    pub fn get_planes_type_b(&self) -> Result<Vec<Plane>, ies_err::Error> {
         todo!()
     }
 
     /// Get the planes from a Type C photometry IES file.
-    pub fn get_planes_type_c(&self) -> Vec<Plane> {
+    /// The vertical angles are used directly as gamma angles, so a file only measured from 90 -> 180 degrees
+    /// gives planes that lie in the upper hemisphere, as for an uplight.
+    /// If the file includes tilt data, each candela value is scaled by the tilt multiplying factor
+    /// interpolated at its vertical angle, i.e. the tilt angles are treated as being measured from
+    /// nadir in the same way as the vertical angles.
+    pub fn get_planes_type_c(&self) -> Result<Vec<Plane>, ies_err::Error> {
+        if self.n_vertical_angles == 0 {
+            return Err(ies_err::Error::NoVerticalAngles);
+        }
+
         // Chunk the intensities into the planes, and give them appropriate angles.
         let mut planes = self
             .candela_values
             .chunks(self.n_vertical_angles)
-            .enumerate()
-            .map(|(iplane, intensities_candelas)| {
+            .zip(self.horizontal_angles.iter())
+            .map(|(intensities_candelas, horizontal_angle)| {
                 let mut curr_plane = Plane::new();
-                curr_plane.set_angle_degrees(self.horizontal_angles[iplane]);
+                curr_plane.set_angle_degrees(*horizontal_angle);
                 curr_plane.set_orientation(PlaneOrientation::Vertical);
-                curr_plane.set_intensities(Vec::from(intensities_candelas));
+                curr_plane.set_intensities(self.apply_tilt(intensities_candelas));
                 curr_plane.set_angles_degrees(&self.vertical_angles);
                 curr_plane.set_units(IntensityUnits::Candela);
                 curr_plane
             })
             .collect::<Vec<Plane>>();
-        
+        if planes.is_empty() {
+            return Ok(planes);
+        }
+
+        // A full set of planes may finish with a 360 degree plane, which duplicates the 0 degree plane.
+        // Drop it, so that the web doesn't contain two planes at the same angle.
+        if planes.len() > 1
+            && (planes.iter().last().unwrap().angle() - 2.0 * PI).abs() <= CLOSING_PLANE_TOLERANCE
+        {
+            planes.pop();
+        }
+
         // Now resolve the symmetries.
         // First, check if we have the first quadrant filled (from 0 -> 90 deg).
         // If so, mirror this to fill the 0 -> 180 degree hemisphere. 
@@ -609,86 +928,113 @@ impl IesFile {
             planes = mirror_first_hemisphere(&planes);
         }
 
-        planes
+        Ok(planes)
     }
-}
-
-impl ToString for IesFile {
-    fn to_string(&self) -> String {
-        let mut output = String::new();
-
-        // Get the standard header.
-        let stan = self.standard.to_string();
-        if !stan.is_empty() {
-            output = format!("{}\n", &stan);
-        };
-
-        // Output keywords
-        output += &self.keywords_to_string();
-
-        // Output the tilt.
-        let tilt_str = match &self.tilt {
-            None => String::from("TILT=NONE\n"),
-            Some(val) => val.to_string(),
-        };
-        output += &tilt_str;
 
-        // Now output the parameters and arrays.
-        output += &format!(
-            "{} {} {} {} {} {} {} {} {} {}\n",
-            self.n_lamps,
-            self.lumens_per_lamp,
-            self.candela_multiplying_factor,
-            self.n_vertical_angles,
-            self.n_horizontal_angles,
-            (self.photometric_type.clone() as usize),
-            self.luminous_opening_units,
-            self.luminous_opening_width,
-            self.luminous_opening_length,
-            self.luminous_opening_height
-        );
-        output += &format!("{} {} {}\n", self.ballast_factor, 1, self.input_watts,);
-        output += &format!(
-            "{}\n",
-            self.vertical_angles
-                .iter()
-                .fold(String::new(), |accum, val| accum + &format!("{} ", val))
-        );
-        output += &format!(
-            "{}\n",
-            self.horizontal_angles
+    /// Scales the candela values of a plane by the tilt multiplying factor at each vertical angle.
+    /// If there is no tilt data, the values are returned unchanged.
+    fn apply_tilt(&self, intensities_candelas: &[f64]) -> Vec<f64> {
+        match self.tilt.tilt() {
+            Some(tilt) => intensities_candelas
                 .iter()
-                .fold(String::new(), |accum, val| accum + &format!("{} ", val))
-        );
-        output += &format!(
-            "{}\n",
-            self.candela_values
-                .chunks(self.n_vertical_angles)
-                .fold(String::new(), |accum, val| accum
-                    + &format!(
-                        "{}\n",
-                        val.iter()
-                            .fold(String::new(), |accum, val| accum + &format!("{} ", val))
-                    ))
-        );
+                .zip(self.vertical_angles.iter())
+                .map(|(intens, ang)| intens * tilt.factor_at_angle(*ang as f32) as f64)
+                .collect(),
+            None => Vec::from(intensities_candelas),
+        }
+    }
+}
+
+impl Default for IesFile {
+    /// Returns an empty file, with a ballast-lamp photometric factor of 1, as is normal for LM-63-2002.
+    fn default() -> Self {
+        IesFile {
+            standard: IesStandard::default(),
+            keywords: HashMap::new(),
+            tilt: TiltRef::default(),
+            n_lamps: 0,
+            lumens_per_lamp: 0.0,
+            candela_multiplying_factor: 0.0,
+            n_vertical_angles: 0,
+            n_horizontal_angles: 0,
+            photometric_type: IesPhotometryType::default(),
+            luminous_opening_units: LuminousOpeningUnits::default(),
+            luminous_opening_width: 0.0,
+            luminous_opening_length: 0.0,
+            luminous_opening_height: 0.0,
+            ballast_factor: 0.0,
+            ballast_lamp_photometric_factor: 1.0,
+            input_watts: 0.0,
+            vertical_angles: Vec::new(),
+            horizontal_angles: Vec::new(),
+            candela_values: Vec::new(),
+            options: ParseOptions::default(),
+            diagnostics: Diagnostics::new(),
+            raw_lines: Vec::new(),
+        }
+    }
+}
 
-        output
+impl std::fmt::Display for IesFile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut output = Vec::new();
+        self.write_to(&mut output).map_err(|_| std::fmt::Error)?;
+        write!(f, "{}", String::from_utf8_lossy(&output))
     }
 }
 
 impl From<IesFile> for PhotometricWeb {
+    /// Converts the file into a photometric web. A malformed file that has no planes gives an empty web,
+    /// use `IesFile::to_photometric_web` to get the error instead.
     fn from(ies: IesFile) -> Self {
-        let mut photweb = PhotometricWeb::new();
-        photweb.set_planes(ies.get_planes());
-        photweb
+        ies.to_photometric_web().unwrap_or_default()
+    }
+}
+
+impl From<&PhotometricWeb> for IesFile {
+    fn from(photweb: &PhotometricWeb) -> Self {
+        IesFile::from_photometric_web(photweb)
+    }
+}
+
+impl TryFrom<&str> for IesFile {
+    type Error = Error;
+
+    /// Parses a file from a string, using the default (strict) parse options.
+    fn try_from(file_string: &str) -> Result<Self, Self::Error> {
+        let mut file = IesFile::new();
+        file.parse(file_string)?;
+        Ok(file)
     }
 }
 
 //TODO: Implement conversion.
 impl PhotometricWebReader for IesFile {
     fn read(&self, path: &Path) -> Result<PhotometricWeb, Error> {
-        let ies_file = Self::parse_file(path)?;
-        let photweb = ies_file.into();
+        let (photweb, _) = self.read_with_diagnostics(path)?;
         Ok(photweb)
     }
+
+    fn read_with_diagnostics(&self, path: &Path) -> Result<(PhotometricWeb, Diagnostics), Error> {
+        let ies_file = Self::parse_file(path)?;
+        let diagnostics = ies_file.diagnostics().clone();
+        Ok((ies_file.to_photometric_web()?, diagnostics))
+    }
+
+    fn read_str_with_diagnostics(
+        &self,
+        contents: &str,
+    ) -> Result<(PhotometricWeb, Diagnostics), Error> {
+        let mut ies_file = IesFile::new();
+        ies_file.parse(contents)?;
+        let diagnostics = ies_file.diagnostics().clone();
+        Ok((ies_file.to_photometric_web()?, diagnostics))
+    }
+}
+
+impl PhotometricWebWriter for IesFile {
+    fn write(photweb: &PhotometricWeb, path: &Path) -> Result<(), Error> {
+        photweb.validate()?;
+        IesFile::from_photometric_web(photweb).to_file(path)
+    }
 }
@@ -1,3 +1,5 @@
+use std::f64::consts::PI;
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum IesLuminousOpening {
     Point,
@@ -62,6 +64,41 @@ pub enum IesLuminousOpening {
 }
 
 impl IesLuminousOpening {
+    /// Returns the area of the opening projected in the direction given by a C-angle and gamma angle (in radians),
+    /// in the square of the units of the dimensions. As in the IES standard, the length runs along the C0-C180
+    /// direction, the width along the C90-C270 direction and the height is vertical. `None` is returned for a
+    /// point source, which has no area, and for the horizontal cylinders and ellipsoidal shapes, whose projection
+    /// is not currently supported.
+    pub fn projected_area(&self, c_angle_rad: f64, gamma_rad: f64) -> Option<f64> {
+        let (c_cos, c_sin) = (c_angle_rad.cos().abs(), c_angle_rad.sin().abs());
+        let (gamma_cos, gamma_sin) = (gamma_rad.cos().abs(), gamma_rad.sin().abs());
+        let ellipse_area = |width: f64, length: f64| PI * width * length / 4.0;
+        match *self {
+            Self::Rectangular { width, length } => Some(width * length * gamma_cos),
+            Self::RectanguarLuminousSides {
+                width,
+                length,
+                height,
+            } => Some(
+                width * length * gamma_cos
+                    + height * gamma_sin * (width * c_cos + length * c_sin),
+            ),
+            Self::Circular { diameter } => Some(ellipse_area(diameter, diameter) * gamma_cos),
+            Self::Ellipse { width, length } => Some(ellipse_area(width, length) * gamma_cos),
+            Self::VerticalCylinder { diameter, height } => Some(
+                ellipse_area(diameter, diameter) * gamma_cos + diameter * height * gamma_sin,
+            ),
+            Self::Sphere { diameter } => Some(ellipse_area(diameter, diameter)),
+            Self::VerticalCircle { diameter } => {
+                Some(ellipse_area(diameter, diameter) * gamma_sin * c_cos)
+            }
+            Self::VerticalEllipse { width, height } => {
+                Some(ellipse_area(width, height) * gamma_sin * c_cos)
+            }
+            _ => None,
+        }
+    }
+
     // Get the type and properties of the luminous opening from the supplied width, lenght and height.
     pub fn from_dimensions(width: f64, length: f64, height: f64) -> Self {
         // Check for the easy case first - point source.
@@ -164,6 +201,8 @@ impl IesLuminousOpening {
 #[cfg(test)]
 mod tests {
     use super::IesLuminousOpening;
+    use approx::assert_relative_eq;
+    use std::f64::consts::PI;
 
     #[test]
     /// In this test we will run through each case in turn and check that we get the correct result.
@@ -304,4 +343,40 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn test_projected_area() {
+        let rectangular = IesLuminousOpening::Rectangular {
+            width: 0.5,
+            length: 2.0,
+        };
+        assert_relative_eq!(rectangular.projected_area(0.0, 0.0).unwrap(), 1.0);
+        assert_relative_eq!(
+            rectangular.projected_area(0.0, PI / 3.0).unwrap(),
+            0.5,
+            epsilon = 1E-12
+        );
+
+        let circular = IesLuminousOpening::Circular { diameter: 2.0 };
+        assert_relative_eq!(circular.projected_area(0.0, 0.0).unwrap(), PI);
+        assert_relative_eq!(
+            circular.projected_area(1.0, PI / 3.0).unwrap(),
+            PI / 2.0,
+            epsilon = 1E-12
+        );
+
+        // Looking from the side, only the luminous sides are seen.
+        let sides = IesLuminousOpening::RectanguarLuminousSides {
+            width: 0.5,
+            length: 2.0,
+            height: 0.1,
+        };
+        assert_relative_eq!(
+            sides.projected_area(PI / 2.0, PI / 2.0).unwrap(),
+            0.2,
+            epsilon = 1E-12
+        );
+
+        assert!(IesLuminousOpening::Point.projected_area(0.0, 0.0).is_none());
+    }
 }
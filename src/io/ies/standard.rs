@@ -1,18 +1,13 @@
 /// A list of possible IES standards.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub enum IesStandard {
+    #[default]
     Iesna1986,
     Iesna1991,
     Iesna1995,
     Iesna2002,
 }
 
-impl Default for IesStandard {
-    fn default() -> Self {
-        Self::Iesna1986
-    }
-}
-
 impl From<&str> for IesStandard {
     /// This converts the header string of the file into a known standard.
     /// The default case will catch anything that
@@ -43,7 +38,6 @@ impl std::fmt::Display for IesStandard {
                 IesStandard::Iesna1995 => "IESNA:LM-63-1995",
                 IesStandard::Iesna2002 => "IESNA:LM-63-2002",
             }
-            .to_string()
         )
     }
 }
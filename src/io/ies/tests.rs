@@ -1,6 +1,15 @@
-use crate::{io::ies::lum_opening::IesLuminousOpening, photweb::PhotometricWeb};
+use crate::{
+    err::Error,
+    io::{ies::lum_opening::IesLuminousOpening, ParseOptions},
+    photweb::{PhotometricWeb, Plane},
+};
+use approx::assert_relative_eq;
+use proptest::prelude::*;
 
-use super::{IesFile, LuminousOpeningUnits};
+use super::{
+    err as ies_err, phot_type::IesPhotometryType, standard::IesStandard, tilt::TiltRef, IesFile,
+    LuminousOpeningUnits,
+};
 
 const IESNA_1991_FILE: &str = "IESNA91
 [TEST] Simple demo intensity distribution 
@@ -23,8 +32,8 @@ TILT=NONE
 #[test]
 fn basic_parse_test() {
     let mut ies = IesFile::new();
-    match ies.parse(&IESNA_1991_FILE.to_owned()) {
-        Err(e) => assert!(false, "Parse error: {}", e),
+    match ies.parse(IESNA_1991_FILE) {
+        Err(e) => panic!("Parse error: {}", e),
         Ok(_) => {
             // Now check that all of the values have made it in from the file.
             assert_eq!(ies.candela_multiplying_factor(), 1.0);
@@ -57,15 +66,30 @@ fn basic_parse_test() {
 
             // Check that angles are valid.
             assert!(IesFile::vertical_angles_valid(
-                &ies.vertical_angles().to_vec()
+                ies.vertical_angles()
             ));
             assert!(IesFile::horizontal_angles_valid(
-                &ies.horizontal_angles().to_vec()
+                ies.horizontal_angles()
             ));
         }
     }
 }
 
+/// Check that each of the fields on the second line of parameters is read.
+#[test]
+fn parse_second_parameter_line_test() {
+    let ies_string = IESNA_1991_FILE.replace("1.0 1.0 0.0", "0.95 0.9 120.0");
+    match IesFile::try_from(ies_string.as_str()) {
+        Ok(ies) => {
+            assert_eq!(ies.ballast_factor(), 0.95);
+            assert_eq!(ies.ballast_lamp_photometric_factor(), 0.9);
+            assert_eq!(ies.input_watts(), 120.0);
+            assert!(ies.to_string().contains("0.95 0.9 120\n"));
+        }
+        Err(e) => panic!("Parse error: {}", e),
+    }
+}
+
 const KEYWORDS_TEST: &str = "[TEST] ABC1234
 [TESTLAB] ABC Laboratories 
 [ISSUEDATE] 18-FEB-2001
@@ -88,7 +112,7 @@ TILT=";
 #[test]
 fn parse_keywords_test() {
     let mut ies = IesFile::new();
-    match ies.parse_keywords(&KEYWORDS_TEST.to_owned()) {
+    match ies.parse_keywords(KEYWORDS_TEST) {
         Ok(_) => {
             assert_eq!(ies.keywords().len(), 16);
 
@@ -108,7 +132,28 @@ fn parse_keywords_test() {
                 Some(&String::from("Make us an offer"))
             );
         }
-        Err(e) => assert!(false, "Keyword parse error: {}", e),
+        Err(e) => panic!("Keyword parse error: {}", e),
+    }
+}
+
+#[test]
+fn keyword_accessors_test() {
+    let mut ies = IesFile::new();
+    ies.parse_keywords(KEYWORDS_TEST).unwrap();
+    assert_eq!(ies.manufacturer(), Some("Aardvark lighting Inc."));
+    assert_eq!(ies.test_lab(), Some("ABC Laboratories"));
+    assert_eq!(ies.lamp_catalog(), Some("MH-400-CLEAR"));
+    assert_eq!(ies.issue_date(), Some("18-FEB-2001"));
+    assert_eq!(IesFile::new().manufacturer(), None);
+}
+
+/// A continuation with no preceding keyword should be reported, rather than panic.
+#[test]
+fn parse_keywords_leading_more_test() {
+    let mut ies = IesFile::new();
+    match ies.parse_keywords("[MORE] continuing nothing\n[TEST] ABC1234\nTILT=NONE") {
+        Err(ies_err::Error::InvalidKeyword(iline)) => assert_eq!(iline, 1),
+        res => panic!("Expected an invalid keyword error, got {:?}", res),
     }
 }
 
@@ -122,9 +167,36 @@ const TILT_TEST: &str = "TILT=INCLUDE
 fn parse_tilt_include_test() {
     let mut ies = IesFile::new();
 
-    match ies.parse_tilt(&TILT_TEST.to_owned()) {
+    match ies.parse_tilt(TILT_TEST) {
         Ok(_) => {}
-        Err(e) => assert!(false, "Tilt parse error: {}", e),
+        Err(e) => panic!("Tilt parse error: {}", e),
+    }
+}
+
+/// Check that the parsed tilt data is accessible, and that the multiplying factors are interpolated.
+#[test]
+fn tilt_getters_test() {
+    let mut ies = IesFile::new();
+    ies.parse_tilt(TILT_TEST).unwrap();
+
+    match ies.tilt().tilt() {
+        Some(tilt) => {
+            assert_eq!(tilt.lamp_to_lumminaire_geometry(), 1);
+            assert_eq!(tilt.no_tilt_angles(), 7);
+            assert_eq!(tilt.angles(), &[0.0, 15.0, 30.0, 45.0, 60.0, 75.0, 90.0]);
+            assert_eq!(
+                tilt.multiplying_factors(),
+                &[1.0, 0.95, 0.94, 0.90, 0.88, 0.87, 0.94]
+            );
+
+            // Tabulated angles return the factor directly, others are interpolated.
+            assert_relative_eq!(tilt.factor_at_angle(45.0), 0.90);
+            assert_relative_eq!(tilt.factor_at_angle(52.5), 0.89, epsilon = 1E-6);
+            // Angles outside of the tabulated range are clamped.
+            assert_relative_eq!(tilt.factor_at_angle(-10.0), 1.0);
+            assert_relative_eq!(tilt.factor_at_angle(120.0), 0.94);
+        }
+        None => panic!("Expected tilt data to be parsed."),
     }
 }
 
@@ -133,11 +205,11 @@ fn parse_tilt_none_test() {
     const TILT_NONE: &str = "TILT=NONE\n";
 
     let mut ies = IesFile::new();
-    match ies.parse_tilt(&TILT_NONE.to_owned()) {
+    match ies.parse_tilt(TILT_NONE) {
         Ok(_) => {
-            assert!(ies.tilt().is_none());
+            assert_eq!(*ies.tilt(), TiltRef::None);
         }
-        Err(e) => assert!(false, "Tilt parse error: {}", e),
+        Err(e) => panic!("Tilt parse error: {}", e),
     }
 }
 
@@ -154,10 +226,73 @@ const VALUES_TEST: &str = "TILT=INCLUDE
 100000 35000 16000 8000 3000
 100000 20000 10000 5000 1000";
 
+/// The same as `VALUES_TEST`, but with the tilt angles and multiplying factors wrapped onto extra lines.
+const WRAPPED_TILT_VALUES_TEST: &str = "TILT=INCLUDE
+1
+13
+0 15 30 45 60 75 90
+105 120 135 150 165 180
+1.0 .95 .94 .90 .88 .87 .98
+.87 .88 .90 .94 .95 1.0
+1 50000 1 5 3 1 1 .5 .6 0
+1.0 1.0 495
+0 22.5 45 67.5 90
+0 45 90
+100000 50000 25000 10000 5000
+100000 35000 16000 8000 3000
+100000 20000 10000 5000 1000";
+
+/// Check that doubled spaces and trailing commas between values don't shift the parsed arrays.
+#[test]
+fn parse_properties_stray_delimiters_test() {
+    let messy = VALUES_TEST
+        .replace("0 22.5 45 67.5 90", "0  22.5,  45 67.5 90,")
+        .replace("100000 35000 16000 8000 3000", "100000,35000, 16000  8000 3000,")
+        .replace("100000 20000 10000 5000 1000", "100000  20000 10000 5000 1000 ");
+
+    let mut ies = IesFile::new();
+    match ies.parse_properties(&messy) {
+        Ok(_) => {
+            let mut expected = IesFile::new();
+            expected.parse_properties(VALUES_TEST).unwrap();
+            assert_eq!(ies.vertical_angles(), expected.vertical_angles());
+            assert_eq!(ies.horizontal_angles(), expected.horizontal_angles());
+            assert_eq!(ies.candela_values(), expected.candela_values());
+        }
+        Err(e) => panic!("Properties parse error: {}", e),
+    }
+}
+
+/// Check that tilt data wrapped across extra lines is read fully, and that the
+/// photometric parameters after it are still read from the correct lines.
+#[test]
+fn parse_wrapped_tilt_test() {
+    let mut ies = IesFile::new();
+    ies.parse_tilt(WRAPPED_TILT_VALUES_TEST).unwrap();
+    match ies.tilt().tilt() {
+        Some(tilt) => {
+            assert_eq!(tilt.angles().len(), 13);
+            assert_eq!(tilt.multiplying_factors().len(), 13);
+            assert_relative_eq!(tilt.factor_at_angle(180.0), 1.0);
+        }
+        None => panic!("Expected tilt data to be parsed."),
+    }
+
+    let mut wrapped = IesFile::new();
+    wrapped.parse_properties(WRAPPED_TILT_VALUES_TEST).unwrap();
+    let mut expected = IesFile::new();
+    expected.parse_properties(VALUES_TEST).unwrap();
+    assert_eq!(wrapped.n_vertical_angles(), 5);
+    assert_eq!(wrapped.input_watts(), 495.0);
+    assert_eq!(wrapped.vertical_angles(), expected.vertical_angles());
+    assert_eq!(wrapped.horizontal_angles(), expected.horizontal_angles());
+    assert_eq!(wrapped.candela_values(), expected.candela_values());
+}
+
 #[test]
 fn parse_properties_test() {
     let mut ies = IesFile::new();
-    match ies.parse_properties(&VALUES_TEST.to_owned()) {
+    match ies.parse_properties(VALUES_TEST) {
         Ok(_) => {
             // Check that the arrays have been correctly read.
             assert_eq!(
@@ -171,13 +306,13 @@ fn parse_properties_test() {
 
             // Check that angles are valid.
             assert!(IesFile::vertical_angles_valid(
-                &ies.vertical_angles().to_vec()
+                ies.vertical_angles()
             ));
             assert!(IesFile::horizontal_angles_valid(
-                &ies.horizontal_angles().to_vec()
+                ies.horizontal_angles()
             ));
         }
-        Err(e) => assert!(false, "Properties parse error: {}", e),
+        Err(e) => panic!("Properties parse error: {}", e),
     }
 }
 
@@ -355,8 +490,8 @@ const EXAMPLE_IESNA2002_TYPEC: &str = include_str!("iesna2002_example_typec.ies"
 #[test]
 fn test_photweb_from_ies_typec() {
     let mut ies = IesFile::new();
-    match ies.parse(&EXAMPLE_IESNA2002_TYPEC.to_owned()) {
-        Err(e) => assert!(false, "Parse error: {}", e),
+    match ies.parse(EXAMPLE_IESNA2002_TYPEC) {
+        Err(e) => panic!("Parse error: {}", e),
         Ok(_) => {
             let photweb: PhotometricWeb = ies.clone().into();
 
@@ -367,3 +502,626 @@ fn test_photweb_from_ies_typec() {
         }
     }
 }
+
+const IESNA_1991_UPLIGHT_FILE: &str = "IESNA91
+[TEST] Simple uplight measured over the upper hemisphere only
+TILT=NONE
+1
+-1
+1
+5
+3
+1
+2
+0.0 0.0 0.0
+1.0 1.0 0.0
+90.0 112.5 135.0 157.5 180.0
+0.0 45.0 90.0
+0.0 300.0 600.0 900.0 1000.0
+0.0 300.0 600.0 900.0 1000.0
+0.0 300.0 600.0 900.0 1000.0
+";
+
+/// Check that a file with vertical angles from 90 -> 180 degrees puts its flux into the upper hemisphere.
+#[test]
+fn test_photweb_from_ies_uplight() {
+    let ies = IesFile::try_from(IESNA_1991_UPLIGHT_FILE).unwrap();
+    assert!(IesFile::vertical_angles_valid(ies.vertical_angles()));
+
+    let photweb = ies.to_photometric_web().unwrap();
+    assert_eq!(photweb.n_planes(), 8);
+    for plane in photweb.planes() {
+        assert_relative_eq!(plane.angles_deg()[0], 90.0);
+        assert_relative_eq!(plane.angles_deg()[4], 180.0);
+    }
+
+    let (lower, upper) = photweb.hemisphere_flux();
+    assert!(upper > 0.0);
+    assert_relative_eq!(lower, 0.0);
+    assert_relative_eq!(upper, photweb.total_flux(), max_relative = 1E-12);
+}
+
+/// Check that the tilt multiplying factors from a TILT=INCLUDE file are applied to the candela values,
+/// interpolating the factor at each vertical angle.
+#[test]
+fn test_photweb_from_ies_tilt_applied() {
+    let mut ies = IesFile::new();
+    ies.parse(EXAMPLE_IESNA2002_TYPEC).unwrap();
+    assert!(ies.tilt().tilt().is_some());
+
+    let photweb: PhotometricWeb = ies.clone().into();
+    let expected = [
+        100000.0 * 1.0,
+        50000.0 * 0.945,
+        25000.0 * 0.90,
+        10000.0 * 0.875,
+        5000.0 * 0.98,
+    ];
+    for (intens, exp) in photweb.planes()[0].intensities().iter().zip(expected) {
+        assert_relative_eq!(*intens, exp, max_relative = 1E-6);
+    }
+
+    // Without the tilt data the candela values are left untouched.
+    ies.set_tilt(TiltRef::None);
+    let photweb: PhotometricWeb = ies.into();
+    assert_eq!(
+        photweb.planes()[0].intensities(),
+        &[100000.0, 50000.0, 25000.0, 10000.0, 5000.0]
+    );
+}
+
+/// Check that the input power makes it across to the web, and that the efficacy
+/// of a zone is the flux in that zone divided by the input watts.
+#[test]
+fn test_zone_efficacy_typec() {
+    let mut ies = IesFile::new();
+    ies.parse(EXAMPLE_IESNA2002_TYPEC).unwrap();
+    let photweb: PhotometricWeb = ies.into();
+    assert_eq!(photweb.input_watts(), Some(495.0));
+
+    // A zone covering the whole sphere should contain all of the flux.
+    let efficacy = photweb.zone_efficacy(0.0, 180.0).unwrap();
+    assert_relative_eq!(efficacy, photweb.total_intensity() / 495.0, epsilon = 1E-9);
+
+    // A narrower zone delivers less light per watt.
+    let narrow = photweb.zone_efficacy(0.0, 40.0).unwrap();
+    assert!(narrow > 0.0 && narrow < efficacy);
+
+    // Without the power metadata we can't compute an efficacy.
+    let mut web = photweb;
+    web.set_input_watts(None);
+    assert!(web.zone_efficacy(0.0, 90.0).is_none());
+}
+
+/// Check that the rated lumens make it across to the web, and that the LOR is relative to them.
+#[test]
+fn test_light_output_ratio_typec() {
+    let mut ies = IesFile::new();
+    ies.parse(EXAMPLE_IESNA2002_TYPEC).unwrap();
+    let photweb: PhotometricWeb = ies.into();
+    assert_eq!(photweb.total_rated_lumens(), Some(50000.0));
+    // The luminous opening is given in feet, and is converted to metres.
+    assert_eq!(
+        photweb.luminous_opening(),
+        Some(&IesLuminousOpening::Rectangular {
+            width: 0.5 * 0.3048,
+            length: 0.6 * 0.3048
+        })
+    );
+    assert_relative_eq!(
+        photweb.light_output_ratio().unwrap(),
+        photweb.total_flux() / 50000.0,
+        epsilon = 1E-12
+    );
+
+    // Absolute photometry has no rated lumens, so no LOR.
+    let absolute =
+        EXAMPLE_IESNA2002_TYPEC.replace("1 50000 1 5 3 1 1 .5 .6 0", "1 -1 1 5 3 1 1 .5 .6 0");
+    let mut ies = IesFile::new();
+    ies.parse(&absolute).unwrap();
+    let photweb: PhotometricWeb = ies.into();
+    assert!(photweb.total_rated_lumens().is_none());
+    assert!(photweb.light_output_ratio().is_none());
+}
+
+/// Check that absolute photometry is flagged, and that the flux computations don't use the -1 lumens as a rating.
+#[test]
+fn test_absolute_photometry() {
+    let mut ies = IesFile::new();
+    ies.parse(EXAMPLE_IESNA2002_TYPEC).unwrap();
+    assert!(!ies.is_absolute_photometry());
+
+    let absolute =
+        EXAMPLE_IESNA2002_TYPEC.replace("1 50000 1 5 3 1 1 .5 .6 0", "1 -1 1 5 3 1 1 .5 .6 0");
+    let mut ies = IesFile::new();
+    ies.parse(&absolute).unwrap();
+    assert!(ies.is_absolute_photometry());
+
+    let photweb = ies.to_photometric_web().unwrap();
+    assert!(photweb.total_rated_lumens().is_none());
+    assert!(photweb.light_output_ratio().is_none());
+    // Without a rating, the zonal cavity method falls back to the total flux of the distribution.
+    assert!(photweb.total_flux() > 0.0);
+    assert!(photweb.zonal_cavity_cu(0.0, (0.0, 0.0, 0.0)) <= 1.0 + 1E-9);
+}
+
+/// Check that a candela block that doesn't fill the grid of angles is rejected when parsing strictly.
+#[test]
+fn test_short_candela_values() {
+    let short =
+        EXAMPLE_IESNA2002_TYPEC.replace("100000 20000 10000 5000 1000", "100000 20000 10000");
+
+    let mut ies = IesFile::new();
+    match ies.parse(&short) {
+        Err(Error::IESError(ies_err::Error::ArrayIncorrectLength(_, expected, found))) => {
+            assert_eq!(expected, 15);
+            assert_eq!(found, 13);
+        }
+        Ok(_) => panic!("Expected the short candela block to be rejected."),
+        Err(e) => panic!("Unexpected error: {}", e),
+    }
+
+    let mut ies = IesFile::new();
+    match ies.parse_with_options(&short, ParseOptions::lenient()) {
+        Ok(_) => {
+            assert_eq!(ies.candela_values().len(), 13);
+            assert_eq!(ies.diagnostics().len(), 1);
+        }
+        Err(e) => panic!("Lenient parse error: {}", e),
+    }
+}
+
+/// Check that the report gathers the individual metrics of the web.
+#[test]
+fn test_photometric_report_typec() {
+    let mut ies = IesFile::new();
+    ies.parse(EXAMPLE_IESNA2002_TYPEC).unwrap();
+    let photweb: PhotometricWeb = ies.into();
+    let report = photweb.photometric_report(50000.0);
+
+    assert_relative_eq!(report.total_flux(), photweb.total_flux(), epsilon = 1E-12);
+    assert_relative_eq!(
+        report.light_output_ratio().unwrap(),
+        photweb.light_output_ratio().unwrap(),
+        epsilon = 1E-12
+    );
+    assert!(report.downward_flux_fraction() > 0.0 && report.downward_flux_fraction() <= 1.0);
+    assert_relative_eq!(report.beam_angle(), photweb.beam_angle(), epsilon = 1E-12);
+    assert!(photweb
+        .iter_samples()
+        .all(|(_, _, intens)| intens <= report.peak_intensity()));
+    assert!(photweb
+        .photometric_report(0.0)
+        .light_output_ratio()
+        .is_none());
+}
+
+/// Check that malformed keyword lines are only accepted when not parsing strictly.
+#[test]
+fn test_parse_options_strict() {
+    let malformed = IESNA_1991_FILE.replace(
+        "[MANUFAC] Lightscape Technologies, Inc.",
+        "[MANUFAC] Lightscape Technologies, Inc.\nThis line is not a keyword",
+    );
+
+    let mut ies = IesFile::new();
+    assert!(ies.parse(&malformed).is_err());
+
+    let mut ies = IesFile::new();
+    assert!(ies
+        .parse_with_options(&malformed, ParseOptions::new())
+        .is_err());
+
+    let mut ies = IesFile::new();
+    match ies.parse_with_options(&malformed, ParseOptions::lenient()) {
+        Ok(_) => {
+            assert_eq!(ies.keywords().len(), 2);
+            assert_eq!(
+                ies.candela_values(),
+                vec![1000.0, 1100.0, 1300.0, 1150.0, 930.0, 650.0, 350.0, 0.0]
+            );
+        }
+        Err(e) => panic!("Lenient parse error: {}", e),
+    }
+
+    // The stored options are used by `parse`, in the same way as for EULUMDAT files.
+    let mut ies = IesFile::new();
+    ies.set_lenient(true);
+    assert!(ies.parse(&malformed).is_ok());
+    assert_eq!(ies.keywords().len(), 2);
+}
+
+/// Check that a recoverable issue is recorded as a diagnostic while the parse still succeeds.
+#[test]
+fn test_parse_diagnostics() {
+    let mut ies = IesFile::new();
+    ies.parse(IESNA_1991_FILE).unwrap();
+    assert!(ies.diagnostics().is_empty());
+
+    let malformed = IESNA_1991_FILE.replace(
+        "[MANUFAC] Lightscape Technologies, Inc.",
+        "[MANUFAC] Lightscape Technologies, Inc.\nThis line is not a keyword",
+    );
+
+    let mut ies = IesFile::new();
+    match ies.parse_with_options(&malformed, ParseOptions::lenient()) {
+        Ok(_) => {
+            assert_eq!(ies.diagnostics().len(), 1);
+            assert_eq!(ies.diagnostics()[0].line(), Some(4));
+            assert!(ies.diagnostics()[0].to_string().starts_with("Line 4:"));
+        }
+        Err(e) => panic!("Lenient parse error: {}", e),
+    }
+}
+
+/// Check that each of the three forms of the TILT line survive writing and re-reading the file.
+#[test]
+fn test_tilt_round_trip() {
+    let tilt_include = "TILT=INCLUDE\n1\n7\n0 15 30 45 60 75 90\n1.0 0.95 0.94 0.90 0.88 0.87 0.94";
+    for tilt_line in ["TILT=NONE", "TILT=lamp_tilt.dat", tilt_include] {
+        let file = IESNA_1991_FILE.replace("TILT=NONE", tilt_line);
+
+        let mut ies = IesFile::new();
+        ies.parse(&file).unwrap();
+        let written = ies.to_string();
+        assert!(written.contains(tilt_line.lines().next().unwrap()));
+
+        let mut reread = IesFile::new();
+        match reread.parse(&written) {
+            Ok(_) => {
+                assert_eq!(reread.tilt(), ies.tilt());
+                assert_eq!(reread.candela_values(), ies.candela_values());
+            }
+            Err(e) => panic!("Round trip parse error for {}: {}", tilt_line, e),
+        }
+    }
+
+    let mut ies = IesFile::new();
+    ies.parse(&IESNA_1991_FILE.replace("TILT=NONE", "TILT=lamp_tilt.dat")).unwrap();
+    assert_eq!(
+        *ies.tilt(),
+        TiltRef::File(std::path::PathBuf::from("lamp_tilt.dat"))
+    );
+}
+
+/// Check that a default file has the same ballast-lamp photometric factor of 1 as a new one, and writes it.
+#[test]
+fn test_default_ballast_lamp_photometric_factor() {
+    assert_eq!(IesFile::default().ballast_lamp_photometric_factor(), 1.0);
+    assert_eq!(IesFile::new().ballast_lamp_photometric_factor(), 1.0);
+
+    // The ballast factor, ballast-lamp photometric factor and input watts share a line.
+    let mut ies = IesFile::default();
+    ies.set_ballast_factor(1.0);
+    ies.set_input_watts(50.0);
+    assert!(ies.to_string().lines().any(|line| line == "1 1 50"));
+}
+
+/// Check that an IES file constructed from a photometric web can have its keywords set before writing.
+#[test]
+fn test_from_photometric_web() {
+    let mut ies = IesFile::new();
+    ies.parse(EXAMPLE_IESNA2002_TYPEC).unwrap();
+    let photweb: PhotometricWeb = ies.clone().into();
+
+    // The symmetric planes in the file are mirrored in the web, so there is a C-plane for each plane in the web.
+    let mut constructed = IesFile::from_photometric_web(&photweb);
+    assert_eq!(constructed.n_vertical_angles(), ies.n_vertical_angles());
+    assert_eq!(constructed.n_horizontal_angles(), photweb.n_planes());
+    assert_eq!(
+        constructed.candela_values().len(),
+        constructed.n_vertical_angles() * constructed.n_horizontal_angles()
+    );
+
+    constructed
+        .mut_keywords()
+        .insert("TEST".to_owned(), "Constructed".to_owned());
+    let mut reread = IesFile::new();
+    match reread.parse(&constructed.to_string()) {
+        Ok(_) => assert_eq!(
+            reread.keywords().get("TEST").map(String::as_str),
+            Some("Constructed")
+        ),
+        Err(e) => panic!("Parse error: {}", e),
+    }
+}
+
+/// Check that streaming a large, high resolution file to disk writes every line, and can be read back.
+#[test]
+fn test_write_to_large_file() {
+    let mut web = PhotometricWeb::new();
+    web.set_planes(
+        (0..360)
+            .map(|c_deg| {
+                let mut plane = Plane::new();
+                plane.set_angle_degrees(c_deg as f64);
+                plane.set_angles_degrees(&(0..181).map(|g_deg| g_deg as f64).collect::<Vec<f64>>());
+                plane.set_intensities(
+                    (0..181)
+                        .map(|g_deg| {
+                            1000.0 * (1.0 + (g_deg as f64).to_radians().cos()) + c_deg as f64
+                        })
+                        .collect::<Vec<f64>>(),
+                );
+                plane
+            })
+            .collect::<Vec<Plane>>(),
+    );
+    let file = IesFile::from_photometric_web(&web);
+
+    let path = std::env::temp_dir().join("lidrs_test_ies_write_to.ies");
+    if let Err(e) = file.to_file(&path) {
+        panic!("Write error: {}", e);
+    }
+    // The header, keywords, tilt, two parameter lines and two lines of angles, then a line per C-plane.
+    let written = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(
+        written.lines().count(),
+        1 + file.keywords().len() + 1 + 2 + 2 + 360
+    );
+
+    match IesFile::parse_file(&path) {
+        Ok(reread) => {
+            assert_eq!(reread.n_vertical_angles(), 181);
+            assert_eq!(reread.n_horizontal_angles(), 360);
+            assert_eq!(reread.vertical_angles(), file.vertical_angles());
+            assert_eq!(reread.horizontal_angles(), file.horizontal_angles());
+            assert_eq!(reread.candela_values(), file.candela_values());
+        }
+        Err(e) => panic!("Read error: {}", e),
+    }
+}
+
+/// Check that a file whose arrays don't match its angle counts can't be written, rather than panicking,
+/// while a file built from an empty web can.
+#[test]
+fn test_write_to_inconsistent() {
+    let mut buffer = Vec::new();
+    assert!(IesFile::from_photometric_web(&PhotometricWeb::new())
+        .write_to(&mut buffer)
+        .is_ok());
+
+    let mut ies = IesFile::new();
+    ies.parse(IESNA_1991_FILE).unwrap();
+    ies.set_n_vertical_angles(0_usize);
+    let mut buffer = Vec::new();
+    match ies.write_to(&mut buffer) {
+        Ok(_) => panic!("Expected a write error."),
+        Err(e) => {
+            assert_eq!(e.kind(), std::io::ErrorKind::InvalidData);
+            assert!(buffer.is_empty());
+        }
+    }
+}
+
+/// Check that the offending line is quoted when the raw lines are kept.
+#[test]
+fn test_describe_error_quotes_line() {
+    let malformed = IESNA_1991_FILE.replace("1000.0 1100.0", "1000.0 1100.x");
+    let iline = malformed
+        .lines()
+        .position(|line| line.contains("1100.x"))
+        .unwrap()
+        + 1;
+
+    let mut options = ParseOptions::new();
+    options.set_keep_raw_lines(true);
+    let mut ies = IesFile::new();
+    match ies.parse_with_options(&malformed, options) {
+        Ok(_) => panic!("Expected a parse error."),
+        Err(e) => {
+            assert!(matches!(
+                e,
+                Error::IESError(ies_err::Error::ParseFloatError(_, _, _))
+            ));
+            assert_eq!(e.line(), Some(iline));
+            assert!(ies.describe_error(&e).ends_with(&format!(
+                "{} | 1000.0 1100.x 1300.0 1150.0 930.0 650.0 350.0 0.0",
+                iline
+            )));
+        }
+    }
+}
+
+/// Check that an error within included tilt data reports, and quotes, its line in the file.
+#[test]
+fn test_describe_error_in_tilt() {
+    let tilt_include = "TILT=INCLUDE\n1\n7\n0 15 30 45 60 75 90\n1.0 0.95 0.94 0.9x 0.88 0.87 0.94";
+    let malformed = IESNA_1991_FILE.replace("TILT=NONE", tilt_include);
+    let iline = malformed
+        .lines()
+        .position(|line| line.contains("0.9x"))
+        .unwrap()
+        + 1;
+
+    let mut options = ParseOptions::new();
+    options.set_keep_raw_lines(true);
+    let mut ies = IesFile::new();
+    match ies.parse_with_options(&malformed, options) {
+        Ok(_) => panic!("Expected a parse error."),
+        Err(e) => {
+            assert_eq!(e.line(), Some(iline));
+            assert!(ies
+                .describe_error(&e)
+                .ends_with(&format!("{} | 1.0 0.95 0.94 0.9x 0.88 0.87 0.94", iline)));
+        }
+    }
+}
+
+/// Check that a closing plane at 360 degrees is dropped, as it duplicates the 0 degree plane.
+#[test]
+fn test_type_c_full_circle() {
+    let full_circle = IESNA_1991_FILE
+        .replace("1\n1\n2\n0.0 0.0 0.0", "5\n1\n2\n0.0 0.0 0.0")
+        .replace(
+            "0.0\n1000.0 1100.0 1300.0 1150.0 930.0 650.0 350.0 0.0",
+            &format!(
+                "0 90 180 270 360\n{}",
+                ["1000.0 1100.0 1300.0 1150.0 930.0 650.0 350.0 0.0"; 5].join("\n")
+            ),
+        );
+
+    let mut ies = IesFile::new();
+    match ies.parse(&full_circle) {
+        Ok(_) => {
+            assert_eq!(ies.n_horizontal_angles(), 5);
+            let planes = ies.get_planes().unwrap();
+            assert_eq!(planes.len(), 4);
+            assert_relative_eq!(planes.last().unwrap().angle_deg(), 270.0);
+
+            let photweb: PhotometricWeb = ies.into();
+            assert_eq!(photweb.n_planes(), 4);
+        }
+        Err(e) => panic!("Parse error: {}", e),
+    }
+}
+
+/// Check that parsing via `TryFrom` gives the same result as parsing into a new file.
+#[test]
+fn test_try_from_str() {
+    let mut ies = IesFile::new();
+    ies.parse(IESNA_1991_FILE).unwrap();
+    match IesFile::try_from(IESNA_1991_FILE) {
+        Ok(parsed) => {
+            assert_eq!(parsed.candela_values(), ies.candela_values());
+            assert_eq!(parsed.vertical_angles(), ies.vertical_angles());
+            assert_eq!(parsed.keywords(), ies.keywords());
+        }
+        Err(e) => panic!("Parse error: {}", e),
+    }
+
+    assert!(IesFile::try_from(EXAMPLE_IESNA2002_TYPEC).is_ok());
+    assert!(matches!(
+        IesFile::try_from(""),
+        Err(Error::IESError(ies_err::Error::EmptyFile))
+    ));
+}
+
+#[test]
+fn get_planes_no_vertical_angles_test() {
+    // A default file has no vertical angles to chunk the candela values by.
+    let ies = IesFile::new();
+    assert!(matches!(
+        ies.get_planes_type_c(),
+        Err(ies_err::Error::NoVerticalAngles)
+    ));
+    assert!(matches!(
+        ies.to_photometric_web(),
+        Err(ies_err::Error::NoVerticalAngles)
+    ));
+    let photweb: PhotometricWeb = ies.into();
+    assert_eq!(photweb.n_planes(), 0);
+}
+
+/// Generates a strictly increasing set of angles (degrees) spanning the given range.
+fn angle_range_strategy(range: (f64, f64), max_interior: usize) -> impl Strategy<Value = Vec<f64>> {
+    let (first, last) = range;
+    prop::collection::vec(first..last, 0..=max_interior).prop_map(move |mut interior| {
+        interior.retain(|angle| *angle > first);
+        interior.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        interior.dedup();
+        [vec![first], interior, vec![last]].concat()
+    })
+}
+
+prop_compose! {
+    /// Generates a valid Type C IES file, with consistent angle and candela arrays.
+    fn type_c_ies_file()(
+        vertical_angles in prop_oneof![Just((0.0, 90.0)), Just((0.0, 180.0)), Just((90.0, 180.0))]
+            .prop_flat_map(|range| angle_range_strategy(range, 18)),
+        horizontal_angles in prop_oneof![
+            Just(vec![0.0]),
+            angle_range_strategy((0.0, 90.0), 8),
+            angle_range_strategy((0.0, 180.0), 16),
+            angle_range_strategy((0.0, 360.0), 32),
+        ],
+        n_lamps in 1usize..10,
+        lumens_per_lamp in prop_oneof![Just(-1.0), 1.0..1E5],
+        candela_multiplying_factor in 0.1..10.0,
+        luminous_opening_units in 1usize..=2,
+        luminous_opening_dims in (0.0..2.0, 0.0..2.0, 0.0..2.0),
+        ballast_factors in (0.5..1.5, 0.5..1.5),
+        input_watts in 1.0..1000.0,
+    )(
+        candela_values in prop::collection::vec(0.0..1E5, vertical_angles.len() * horizontal_angles.len()),
+        vertical_angles in Just(vertical_angles),
+        horizontal_angles in Just(horizontal_angles),
+        n_lamps in Just(n_lamps),
+        lumens_per_lamp in Just(lumens_per_lamp),
+        candela_multiplying_factor in Just(candela_multiplying_factor),
+        luminous_opening_units in Just(luminous_opening_units),
+        luminous_opening_dims in Just(luminous_opening_dims),
+        ballast_factors in Just(ballast_factors),
+        input_watts in Just(input_watts),
+    ) -> IesFile {
+        let mut ies = IesFile::new();
+        ies.set_standard(IesStandard::Iesna2002);
+        ies.mut_keywords().insert("TEST".to_string(), "Round trip".to_string());
+        ies.set_n_lamps(n_lamps);
+        ies.set_lumens_per_lamp(lumens_per_lamp);
+        ies.set_candela_multiplying_factor(candela_multiplying_factor);
+        ies.set_n_vertical_angles(vertical_angles.len());
+        ies.set_n_horizontal_angles(horizontal_angles.len());
+        ies.set_photometric_type(IesPhotometryType::TypeC);
+        ies.set_luminous_opening_units(LuminousOpeningUnits::from(luminous_opening_units));
+        ies.set_luminous_opening_width(luminous_opening_dims.0);
+        ies.set_luminous_opening_length(luminous_opening_dims.1);
+        ies.set_luminous_opening_height(luminous_opening_dims.2);
+        ies.set_ballast_factor(ballast_factors.0);
+        ies.set_ballast_lamp_photometric_factor(ballast_factors.1);
+        ies.set_input_watts(input_watts);
+        ies.set_vertical_angles(vertical_angles);
+        ies.set_horizontal_angles(horizontal_angles);
+        ies.set_candela_values(candela_values);
+        ies
+    }
+}
+
+proptest! {
+    /// Writing a valid Type C file and parsing it back should preserve all of the numeric fields.
+    #[test]
+    fn round_trip_type_c_test(ies in type_c_ies_file()) {
+        let mut parsed = IesFile::new();
+        parsed.parse(&ies.to_string()).unwrap();
+
+        prop_assert_eq!(parsed.photometric_type(), ies.photometric_type());
+        prop_assert_eq!(parsed.n_lamps(), ies.n_lamps());
+        prop_assert_eq!(parsed.lumens_per_lamp(), ies.lumens_per_lamp());
+        prop_assert_eq!(parsed.candela_multiplying_factor(), ies.candela_multiplying_factor());
+        prop_assert_eq!(parsed.n_vertical_angles(), ies.n_vertical_angles());
+        prop_assert_eq!(parsed.n_horizontal_angles(), ies.n_horizontal_angles());
+        prop_assert_eq!(parsed.luminous_opening_units(), ies.luminous_opening_units());
+        prop_assert_eq!(parsed.luminous_opening_width(), ies.luminous_opening_width());
+        prop_assert_eq!(parsed.luminous_opening_length(), ies.luminous_opening_length());
+        prop_assert_eq!(parsed.luminous_opening_height(), ies.luminous_opening_height());
+        prop_assert_eq!(parsed.ballast_factor(), ies.ballast_factor());
+        prop_assert_eq!(
+            parsed.ballast_lamp_photometric_factor(),
+            ies.ballast_lamp_photometric_factor()
+        );
+        prop_assert_eq!(parsed.input_watts(), ies.input_watts());
+        prop_assert_eq!(parsed.vertical_angles(), ies.vertical_angles());
+        prop_assert_eq!(parsed.horizontal_angles(), ies.horizontal_angles());
+        prop_assert_eq!(parsed.candela_values(), ies.candela_values());
+        prop_assert!(IesFile::vertical_angles_valid(parsed.vertical_angles()));
+        prop_assert!(IesFile::horizontal_angles_valid(parsed.horizontal_angles()));
+    }
+}
+
+#[test]
+fn parse_bom_and_crlf_test() {
+    for file in [IESNA_1991_FILE, EXAMPLE_IESNA2002_TYPEC] {
+        let mut clean = IesFile::new();
+        clean.parse(file).unwrap();
+
+        let windows = format!("\u{feff}{}", file.replace('\n', "\r\n"));
+        let mut ies = IesFile::new();
+        ies.parse(&windows).unwrap();
+
+        assert_eq!(ies.standard(), clean.standard());
+        assert_eq!(ies.keywords(), clean.keywords());
+        assert_eq!(ies.vertical_angles(), clean.vertical_angles());
+        assert_eq!(ies.horizontal_angles(), clean.horizontal_angles());
+        assert_eq!(ies.candela_values(), clean.candela_values());
+    }
+}
@@ -1,20 +1,58 @@
+use property::Property;
 use regex::Regex;
 
 use super::Error;
 use std::{
     fs::File,
     io::{BufReader, Read},
-    path::Path,
+    path::{Path, PathBuf},
 };
 
 use super::DELIMITERS_PATTERN;
 
+/// The tilt information referenced by an IES file, as given by the `TILT=` line.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum TiltRef {
+    /// `TILT=NONE`: the lamp output does not vary with tilt.
+    #[default]
+    None,
+    /// `TILT=INCLUDE`: the tilt data is included in the file.
+    Include(Tilt),
+    /// `TILT=<filename>`: the tilt data is stored in a separate file, which is not read.
+    File(PathBuf),
+}
+
+impl TiltRef {
+    /// Returns the tilt data, if it is included in the file.
+    pub fn tilt(&self) -> Option<&Tilt> {
+        match self {
+            Self::Include(tilt) => Some(tilt),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for TiltRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::None => writeln!(f, "TILT=NONE"),
+            Self::Include(tilt) => write!(f, "{}", tilt),
+            Self::File(path) => writeln!(f, "TILT={}", path.display()),
+        }
+    }
+}
+
 /// A struct for representing tilt angles in lumminaires.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq, Property)]
+#[property(get(public))]
 pub struct Tilt {
+    /// The orientation of the lamp within the lumminaire.
     lamp_to_lumminaire_geometry: usize,
+    /// The number of tilt angles (and multiplying factors).
     no_tilt_angles: usize,
+    /// The tilt angles (degrees).
     angles: Vec<f32>,
+    /// The multiplying factors corresponding to each tilt angle.
     multiplying_factors: Vec<f32>,
 }
 
@@ -33,111 +71,140 @@ impl Tilt {
     }
 
     pub fn parse(tilt_str: &str) -> Result<Option<Tilt>, Error> {
+        let (tilt, n_lines) = Tilt::parse_lines(tilt_str, 1)?;
+
+        // Anything other than blank lines after the end of the tilt data is an error.
+        match tilt_str
+            .lines()
+            .enumerate()
+            .skip(n_lines)
+            .find(|(_, line)| !line.trim().is_empty())
+        {
+            Some((iline, _)) => Err(Error::TiltFiltTooLong(iline + 1)),
+            None => Ok(Some(tilt)),
+        }
+    }
+
+    /// Parses the tilt data from the start of the string, returning the tilt and the number of lines it spans.
+    /// The tilt angles and multiplying factors may each be wrapped across several lines, as is common in files
+    /// with many tilt angles, but the multiplying factors must start on a new line. Any lines after the end of
+    /// the tilt data are ignored. `first_line` is the (1-based) line number of the first line of the tilt data
+    /// within its file, so that errors report the line in the file rather than within the tilt data.
+    pub fn parse_lines(tilt_str: &str, first_line: usize) -> Result<(Tilt, usize), Error> {
         let split_regex = Regex::new(DELIMITERS_PATTERN).unwrap();
         let mut tilt = Tilt::new();
-        let errors: Vec<Result<(), Error>> = tilt_str
+        let mut lines = tilt_str
             .lines()
-            .into_iter()
             .enumerate()
-            .map(|(iline, line)| {
-                match iline {
-                    // Get the lamp to lumminaire geometry.
-                    0 => match line.parse::<u8>() {
-                        Ok(val) => {
-                            tilt.lamp_to_lumminaire_geometry = val as usize;
-                            Ok(())
-                        }
-                        Err(e) => Err(crate::io::ies::Error::ParseIntError(iline, None, e)),
-                    },
-                    // Get the number of tilt angles.
-                    1 => match line.parse::<u8>() {
-                        Ok(val) => {
-                            tilt.no_tilt_angles = val as usize;
-                            Ok(())
-                        }
-                        Err(e) => Err(crate::io::ies::Error::ParseIntError(iline, None, e)),
-                    },
-                    // Get the tilt angles.
-                    2 => {
-                        let (vals, errs): (Vec<_>, Vec<_>) = split_regex
-                            .split(line)
-                            .map(|str| str.parse::<f32>())
-                            .partition(Result::is_ok);
-                        let numbers: Vec<_> = vals.into_iter().map(Result::unwrap).collect();
-                        let errors: Vec<_> = errs.into_iter().map(Result::unwrap_err).collect();
-                        match errors.first() {
-                            None => {
-                                if numbers.len() == tilt.no_tilt_angles {
-                                    tilt.angles = numbers;
-                                    Ok(())
-                                } else {
-                                    Err(crate::io::ies::Error::ArrayIncorrectLength(
-                                        iline,
-                                        tilt.no_tilt_angles,
-                                        numbers.len(),
-                                    ))
-                                }
-                            }
-                            Some(e) => Err(crate::io::ies::Error::ParseFloatError(
-                                iline,
-                                None,
-                                e.clone(),
-                            )),
-                        }
-                    }
-                    // Get the multiplying factors.
-                    3 => {
-                        let (vals, errs): (Vec<_>, Vec<_>) = split_regex
-                            .split(line)
-                            .map(|str| str.parse::<f32>())
-                            .partition(Result::is_ok);
-                        let numbers: Vec<_> = vals.into_iter().map(Result::unwrap).collect();
-                        let errors: Vec<_> = errs.into_iter().map(Result::unwrap_err).collect();
-                        match errors.first() {
-                            None => {
-                                if numbers.len() == tilt.no_tilt_angles {
-                                    tilt.multiplying_factors = numbers;
-                                    Ok(())
-                                } else {
-                                    Err(crate::io::ies::Error::ArrayIncorrectLength(
-                                        iline,
-                                        tilt.no_tilt_angles,
-                                        numbers.len(),
-                                    ))
-                                }
-                            }
-                            Some(e) => Err(crate::io::ies::Error::ParseFloatError(
-                                iline,
-                                None,
-                                e.clone(),
-                            )),
-                        }
-                    }
-                    _ => Err(Error::TiltFiltTooLong(iline)),
+            .map(|(iline, line)| (first_line + iline, line));
+
+        // Get the lamp to lumminaire geometry.
+        let (iline, line) = lines.next().ok_or(Error::UnexpectedEndOfFile(first_line))?;
+        tilt.lamp_to_lumminaire_geometry = match line.trim().parse::<u8>() {
+            Ok(val) => val as usize,
+            Err(e) => return Err(Error::ParseIntError(iline, None, e)),
+        };
+
+        // Get the number of tilt angles.
+        let (iline, line) = lines
+            .next()
+            .ok_or(Error::UnexpectedEndOfFile(first_line + 1))?;
+        tilt.no_tilt_angles = match line.trim().parse::<u8>() {
+            Ok(val) => val as usize,
+            Err(e) => return Err(Error::ParseIntError(iline, None, e)),
+        };
+
+        // Get the tilt angles, followed by the multiplying factors.
+        let (angles, n_angle_lines) = Self::parse_values(
+            &mut lines,
+            &split_regex,
+            tilt.no_tilt_angles,
+            first_line + 2,
+        )?;
+        let (factors, n_factor_lines) = Self::parse_values(
+            &mut lines,
+            &split_regex,
+            tilt.no_tilt_angles,
+            first_line + 2 + n_angle_lines,
+        )?;
+        tilt.angles = angles;
+        tilt.multiplying_factors = factors;
+
+        Ok((tilt, 2 + n_angle_lines + n_factor_lines))
+    }
+
+    /// Reads lines until `n_values` numbers have been read, returning the values and the number of lines read.
+    /// At least one line is always read, and an error is returned if the final line overruns the expected length.
+    /// `first_line` is the line number of the first line to be read, used when reporting a premature end of the data.
+    fn parse_values<'a, I: Iterator<Item = (usize, &'a str)>>(
+        lines: &mut I,
+        split_regex: &Regex,
+        n_values: usize,
+        first_line: usize,
+    ) -> Result<(Vec<f32>, usize), Error> {
+        let mut values: Vec<f32> = Vec::with_capacity(n_values);
+        let mut n_lines = 0;
+        loop {
+            let (iline, line) = lines
+                .next()
+                .ok_or(Error::UnexpectedEndOfFile(first_line + n_lines))?;
+            n_lines += 1;
+            for val_str in split_regex.split(line.trim()).filter(|s| !s.is_empty()) {
+                match val_str.parse::<f32>() {
+                    Ok(val) => values.push(val),
+                    Err(e) => return Err(Error::ParseFloatError(iline, None, e)),
                 }
-            })
-            .filter(Result::is_err)
-            .collect();
+            }
 
-        match errors.first() {
-            None => Ok(Some(tilt)),
-            Some(err) => Err(err.as_ref().unwrap_err().clone()),
+            if values.len() > n_values {
+                return Err(Error::ArrayIncorrectLength(iline, n_values, values.len()));
+            } else if values.len() == n_values {
+                return Ok((values, n_lines));
+            }
+        }
+    }
+}
+
+impl Tilt {
+    /// Returns the multiplying factor for the given tilt angle (in degrees), linearly interpolating
+    /// between the tabulated angles. Angles outside of the tabulated range are clamped to the first
+    /// or last factor, and a tilt without any factors returns a factor of 1.0.
+    pub fn factor_at_angle(&self, angle_deg: f32) -> f32 {
+        let n = self.angles.len().min(self.multiplying_factors.len());
+        if n == 0 {
+            return 1.0;
+        }
+
+        let angles = &self.angles[..n];
+        let factors = &self.multiplying_factors[..n];
+        match angles.iter().position(|ang| *ang >= angle_deg) {
+            Some(0) => factors[0],
+            Some(i) => {
+                let frac = (angle_deg - angles[i - 1]) / (angles[i] - angles[i - 1]);
+                factors[i - 1] + frac * (factors[i] - factors[i - 1])
+            }
+            None => factors[n - 1],
         }
     }
 }
 
-impl ToString for Tilt {
-    fn to_string(&self) -> String {
-        format!(
+impl std::fmt::Display for Tilt {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
             "TILT=INCLUDE\n{}\n{}\n{}\n{}\n",
             self.lamp_to_lumminaire_geometry,
             self.no_tilt_angles,
             self.angles
                 .iter()
-                .fold("".to_string(), |accum, val| accum + &format!("{} ", val)),
+                .map(|val| val.to_string())
+                .collect::<Vec<String>>()
+                .join(" "),
             self.multiplying_factors
                 .iter()
-                .fold("".to_string(), |accum, val| accum + &format!("{} ", val)),
+                .map(|val| val.to_string())
+                .collect::<Vec<String>>()
+                .join(" "),
         )
     }
 }
@@ -6,7 +6,8 @@ pub fn parse_float_array(str: &str) -> Result<Vec<f32>, Error> {
     let split_regex = Regex::new(DELIMITERS_PATTERN).unwrap();
 
     let (vals, errs): (Vec<_>, Vec<_>) = split_regex
-        .split(str)
+        .split(str.trim())
+        .filter(|str| !str.is_empty())
         .map(|str| str.parse::<f32>())
         .partition(Result::is_ok);
 
@@ -3,3 +3,22 @@
 // Available photometric file formats.
 pub mod eulumdat;
 pub mod ies;
+
+// Shared parser configuration.
+pub mod diagnostics;
+pub mod options;
+pub use diagnostics::{Diagnostic, Diagnostics};
+pub use options::ParseOptions;
+
+// Optional input formats.
+#[cfg(feature = "gzip")]
+pub(crate) mod gzip;
+
+/// Normalises raw file text before parsing, stripping any leading UTF-8 byte order
+/// mark and converting Windows (CRLF) line endings to bare newlines.
+pub(crate) fn normalise_text(input: &str) -> String {
+    input
+        .strip_prefix('\u{feff}')
+        .unwrap_or(input)
+        .replace("\r\n", "\n")
+}
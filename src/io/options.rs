@@ -0,0 +1,39 @@
+use property::Property;
+
+/// Options controlling how the file parsers behave.
+/// By default the parsers are strict, returning an error for anything that does not conform to the format.
+#[derive(Debug, Clone, PartialEq, Property)]
+#[property(get(public), set(public))]
+pub struct ParseOptions {
+    /// Whether to error on malformed input. If `false`, recoverable issues (such as malformed
+    /// keyword lines or extra lines at the end of a file) are skipped instead.
+    strict: bool,
+    /// Whether to keep the raw text of each line of the file, so that errors can quote the offending line.
+    keep_raw_lines: bool,
+}
+
+impl ParseOptions {
+    /// Returns a new instance of the parse options with default (strict) values.
+    pub fn new() -> Self {
+        Self {
+            ..Default::default()
+        }
+    }
+
+    /// Returns a new instance of the parse options that skips recoverable issues.
+    pub fn lenient() -> Self {
+        Self {
+            strict: false,
+            ..Default::default()
+        }
+    }
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self {
+            strict: true,
+            keep_raw_lines: false,
+        }
+    }
+}
@@ -6,6 +6,7 @@
 //! - Illuminating Engineering Society (.ies) formatted ASCII files.
 //!
 //! It is also able to interpret these files to produce a full spherical photometric web from the inputs.
+//! Files can be converted between the supported formats using `convert_file`.
 //!
 
 pub mod err;
@@ -13,3 +14,51 @@ pub mod io;
 pub mod ops;
 pub mod photweb;
 pub mod util;
+
+use std::path::Path;
+
+/// Converts a photometric file from one supported format to another, such as from IES to EULUMDAT.
+/// The input is read using the reader for its file extension, as in `PhotometricWeb::from_path`, and the
+/// output is written using the writer for its file extension, as in `PhotometricWeb::write_file`.
+pub fn convert_file(input: &Path, output: &Path) -> Result<(), err::Error> {
+    photweb::PhotometricWeb::from_path(input)?.write_file(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::convert_file;
+    use crate::{err::Error, photweb::PhotometricWeb};
+    use approx::assert_relative_eq;
+    use std::path::Path;
+
+    /// Check that an IES file converted to EULUMDAT describes the same distribution.
+    #[test]
+    fn test_convert_file() {
+        let input = Path::new("./src/io/ies/iesna2002_example_typec.ies");
+        let dir = std::env::temp_dir().join("lidrs_test_convert_file");
+        std::fs::create_dir_all(&dir).unwrap();
+        let output = dir.join("converted.ldt");
+
+        if let Err(e) = convert_file(input, &output) {
+            panic!("Conversion error: {}", e);
+        }
+        let original = PhotometricWeb::from_path(input).unwrap();
+        match PhotometricWeb::from_path(&output) {
+            Ok(converted) => {
+                assert_eq!(converted.n_planes(), original.n_planes());
+                assert_relative_eq!(
+                    converted.total_flux(),
+                    original.total_flux(),
+                    max_relative = 1E-6
+                );
+            }
+            Err(e) => panic!("Read error: {}", e),
+        }
+
+        assert!(matches!(
+            convert_file(input, &dir.join("converted.txt")),
+            Err(Error::InvalidFileType(_))
+        ));
+    }
+}
+
@@ -6,17 +6,29 @@ pub enum Error {
     InconsistentNumberOfPlanes(usize, usize, usize),
     InconsistentIntensitiesInPlane(usize, usize),
     InconsistentPlaneAngles,
+    SymmetryNotSatisfied,
+    DuplicatePlaneAngle(f64),
+    InconsistentSamplesInPlanes(usize, usize, usize),
+    PlaneAnglesNotIncreasing(usize),
+    IncompatibleUnits(usize),
 }
 
 impl Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", {
             match self {
-                Self::NoPlanes => format!("No photometric planes found in web.. "),
+                Self::NoPlanes => "No photometric planes found in web.. ".to_string(),
                 Self::InconsistentNumberOfPlanes(ref expect, ref found, ref idx) => format!("Expected {} planes. Found {} planes and index {}. ", expect, found, idx),
                 Self::InconsistentIntensitiesInPlane(ref expect, ref found) => format!("Expected {} intensities in plane. Found {} intensisites. ", expect, found),
-                Self::InconsistentPlaneAngles => format!("Angles are inconsistent between photometric web planes. "),
+                Self::InconsistentPlaneAngles => "Angles are inconsistent between photometric web planes. ".to_string(),
+                Self::SymmetryNotSatisfied => "The photometric web does not satisfy the requested symmetry. ".to_string(),
+                Self::DuplicatePlaneAngle(ref angle_deg) => format!("Found more than one plane at C{} degrees. ", angle_deg),
+                Self::InconsistentSamplesInPlanes(ref expect, ref found, ref idx) => format!("Expected {} samples in each plane. Found {} samples in plane {}. ", expect, found, idx),
+                Self::PlaneAnglesNotIncreasing(ref idx) => format!("Plane angles are not increasing at plane {}. ", idx),
+                Self::IncompatibleUnits(ref idx) => format!("Plane {} is not in units of candela. ", idx),
             }
         })
     }
 }
+
+impl std::error::Error for Error {}
@@ -5,6 +5,9 @@ use crate::{
     photweb::{PhotometricWeb, Plane}
 };
 
+/// The tolerance (in radians) within which angles are considered the same when building a common grid.
+const GRID_ANGLE_TOLERANCE: f64 = 1E-6;
+
 /// This function will average the provided photmetric webs, making some assumptions about their structure:
 /// - First, it assumes that they have the same number of planes, and that those planes are at the same angles.
 /// - It also assumes that the intensities in the planes are the same, and that they are at the same angles.
@@ -13,6 +16,92 @@ use crate::{
 /// or anything like that, it will only average the intensity values in identically strcutured webs. 
 /// Before performing the operation, it will check that it is possible, and return an appropriate Error is not. 
 pub fn average_photmetric_web_intensities(input_webs: Vec<&PhotometricWeb>) -> Result<PhotometricWeb, Error> {
+    combine_photometric_web_intensities(input_webs, |values| values.iter().sum::<f64>() / values.len() as f64)
+}
+
+/// This function will average the provided photometric webs, without requiring them to be identically structured.
+/// The union of all of the C-angles and gamma angles across the webs is used as a common grid, and each of
+/// the webs is resampled onto it using `PhotometricWeb::intensity_at` before the intensities are averaged.
+/// If no webs are provided, or any of the webs have no planes, an Error is returned.
+pub fn average_auto(input_webs: Vec<&PhotometricWeb>) -> Result<PhotometricWeb, Error> {
+    if input_webs.is_empty() || input_webs.iter().any(|web| web.n_planes() == 0) {
+        return Err(Error::NoPlanes);
+    }
+
+    let c_angles = union_angles(
+        input_webs
+            .iter()
+            .flat_map(|web| web.planes().iter().map(|pl| pl.angle())),
+    );
+    let gamma_angles = union_angles(input_webs.iter().flat_map(|web| {
+        web.planes()
+            .iter()
+            .flat_map(|pl| pl.angles().iter().copied())
+    }));
+
+    let resampled_webs: Vec<PhotometricWeb> = input_webs
+        .iter()
+        .map(|web| {
+            let planes = c_angles
+                .iter()
+                .map(|c_angle| {
+                    let mut plane = Plane::new();
+                    plane.set_angle(*c_angle);
+                    plane.set_angles(gamma_angles.clone());
+                    plane.set_intensities(
+                        gamma_angles
+                            .iter()
+                            .map(|gamma| web.intensity_at(*c_angle, *gamma))
+                            .collect::<Vec<f64>>(),
+                    );
+                    plane
+                })
+                .collect();
+            let mut resampled = PhotometricWeb::new();
+            resampled.set_planes(planes);
+            resampled
+        })
+        .collect();
+
+    average_photmetric_web_intensities(resampled_webs.iter().collect())
+}
+
+/// Sorts the provided angles, removing any that are within `GRID_ANGLE_TOLERANCE` of one another.
+fn union_angles<I: Iterator<Item = f64>>(angles: I) -> Vec<f64> {
+    let mut angles: Vec<f64> = angles.collect();
+    angles.sort_by(f64::total_cmp);
+    angles.dedup_by(|ang, prev| (*ang - *prev).abs() <= GRID_ANGLE_TOLERANCE);
+    angles
+}
+
+/// This function will sum the intensities of the provided photometric webs, for example to find the distribution
+/// of a luminaire with several independently measured sources. It makes the same assumptions about the structure
+/// of the webs as `average_photmetric_web_intensities`, and will return an appropriate Error if they are not met.
+pub fn sum_photometric_web_intensities(input_webs: Vec<&PhotometricWeb>) -> Result<PhotometricWeb, Error> {
+    combine_photometric_web_intensities(input_webs, |values| values.iter().sum::<f64>())
+}
+
+/// This function will subtract the intensities of the second photometric web from the first, for example to remove
+/// a background measurement. The webs must be identically structured, as in `average_photmetric_web_intensities`.
+pub fn difference_photometric_web_intensities(web: &PhotometricWeb, other: &PhotometricWeb) -> Result<PhotometricWeb, Error> {
+    combine_photometric_web_intensities(vec![web, other], |values| values[0] - values[1])
+}
+
+/// This function will linearly interpolate between the intensities of two photometric webs, for example to blend
+/// between two states of a luminaire. The intensities are `(1 - t) * a + t * b`, with `t` clamped to the range
+/// 0 -> 1, so that a `t` of 0 returns `a` and 1 returns `b`. The webs must be identically structured, as in
+/// `average_photmetric_web_intensities`, so webs with different grids should be resampled beforehand.
+pub fn interpolate_photometric_webs(a: &PhotometricWeb, b: &PhotometricWeb, t: f64) -> Result<PhotometricWeb, Error> {
+    let t = t.clamp(0.0, 1.0);
+    combine_photometric_web_intensities(vec![a, b], |values| (1.0 - t) * values[0] + t * values[1])
+}
+
+/// Checks that the provided photometric webs are identically structured, and then combines the intensities
+/// at each sample using the provided function, which is given the intensities of each web at that sample.
+fn combine_photometric_web_intensities<F>(input_webs: Vec<&PhotometricWeb>, combine: F) -> Result<PhotometricWeb, Error>
+where
+    F: Fn(&[f64]) -> f64,
+{
     // Check that all have the same number of planes.
     let n_planes_vec: Vec<usize> = input_webs
         .iter()
@@ -20,7 +109,7 @@ pub fn average_photmetric_web_intensities(input_webs: Vec<&PhotometricWeb>) -> R
         .collect();
 
     // Check that we have more than zero planes, else we are done. 
-    if n_planes_vec.len() == 0 { return Err(Error::NoPlanes) };
+    if n_planes_vec.is_empty() { return Err(Error::NoPlanes) };
     
     if n_planes_vec.iter().min() != n_planes_vec.iter().max() {
         let idx = n_planes_vec.windows(2).position(|vals| vals[0] != vals[1]).unwrap();
@@ -28,6 +117,7 @@ pub fn average_photmetric_web_intensities(input_webs: Vec<&PhotometricWeb>) -> R
     };
 
     let n_planes = *n_planes_vec.first().unwrap();
+    if n_planes == 0 { return Err(Error::NoPlanes) };
 
     // Assemble angles and Check that all have the same angles in their planes. 
     let plane_angles: Vec<f64> = input_webs
@@ -48,26 +138,179 @@ pub fn average_photmetric_web_intensities(input_webs: Vec<&PhotometricWeb>) -> R
         }
     };
     
-    let n_samples = input_webs.len();
     // Checking out of the way. Now construct the planes, and the photometric web object.
+    // The intensities are looked up with bounds checking, in case the planes have fewer intensities than angles.
     let planes = (0..n_planes).map(|i_plane| {
-        let average_intensities: Vec<f64> = angles.iter().enumerate().map(|(idx, _)| {
-            input_webs
+        let combined_intensities: Vec<f64> = (0..angles.len()).map(|idx| {
+            let values: Option<Vec<f64>> = input_webs
             .iter()
-            .map(|web| web.planes()[i_plane]
-            .intensities()[idx])
-            .sum::<f64>() / n_samples as f64
-        }).collect();
+            .map(|web| web.intensity(i_plane, idx))
+            .collect();
+            values.map(|values| combine(&values))
+        }).collect::<Option<Vec<f64>>>()
+        .ok_or_else(|| {
+            let found = input_webs.iter().map(|web| web.planes()[i_plane].intensities().len()).min().unwrap_or(0);
+            Error::InconsistentIntensitiesInPlane(angles.len(), found)
+        })?;
 
         // Assemble the Plane.
         let mut plane = Plane::new();
         plane.set_angle(plane_angles[i_plane]);
         plane.set_angles(angles.clone());
-        plane.set_intensities(average_intensities);
-        plane
-    }).collect();
+        plane.set_intensities(combined_intensities);
+        Ok(plane)
+    }).collect::<Result<Vec<Plane>, Error>>()?;
 
     let mut photweb = PhotometricWeb::new();
     photweb.set_planes(planes);
     Ok(photweb)
-}
\ No newline at end of file
+}
+
+/// This function scales all of the intensities in the provided photometric web by a constant factor,
+/// returning a new photometric web with the same structure.
+pub fn scale_photometric_web(input_web: &PhotometricWeb, factor: f64) -> PhotometricWeb {
+    let planes = input_web
+        .planes()
+        .iter()
+        .map(|pl| {
+            let mut plane = pl.clone();
+            plane.set_intensities(pl.intensities().iter().map(|int| int * factor).collect::<Vec<f64>>());
+            plane
+        })
+        .collect();
+
+    let mut photweb = PhotometricWeb::new();
+    photweb.set_planes(planes);
+    photweb.copy_metadata_from(input_web);
+    photweb
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        average_auto, difference_photometric_web_intensities, err::Error,
+        interpolate_photometric_webs, sum_photometric_web_intensities,
+    };
+    use crate::photweb::{PhotometricWeb, Plane};
+
+    /// Builds a web of two planes, with three samples each, all set to the provided intensity.
+    fn constant_web(intensity: f64) -> PhotometricWeb {
+        let planes = [0.0, 90.0]
+            .iter()
+            .map(|angle| {
+                let mut plane = Plane::new();
+                plane.set_angle_degrees(*angle);
+                plane.set_angles_degrees(&[0.0, 90.0, 180.0]);
+                plane.set_intensities(vec![intensity; 3]);
+                plane
+            })
+            .collect();
+        let mut web = PhotometricWeb::new();
+        web.set_planes(planes);
+        web
+    }
+
+    #[test]
+    fn test_sum_and_difference() {
+        let (web_a, web_b) = (constant_web(3.0), constant_web(1.0));
+
+        match sum_photometric_web_intensities(vec![&web_a, &web_b, &web_b]) {
+            Ok(sum) => sum
+                .planes()
+                .iter()
+                .for_each(|pl| assert_eq!(pl.intensities(), &[5.0, 5.0, 5.0])),
+            Err(e) => panic!("Sum error: {}", e),
+        }
+
+        match difference_photometric_web_intensities(&web_a, &web_b) {
+            Ok(diff) => diff
+                .planes()
+                .iter()
+                .for_each(|pl| assert_eq!(pl.intensities(), &[2.0, 2.0, 2.0])),
+            Err(e) => panic!("Difference error: {}", e),
+        }
+
+        // Webs with different structures cannot be combined.
+        let mut web_c = constant_web(1.0);
+        web_c.set_planes(vec![web_b.planes()[0].clone()]);
+        assert!(matches!(
+            difference_photometric_web_intensities(&web_a, &web_c),
+            Err(Error::InconsistentNumberOfPlanes(2, 1, 1))
+        ));
+    }
+
+    #[test]
+    fn test_interpolate_photometric_webs() {
+        let (web_a, web_b) = (constant_web(3.0), constant_web(1.0));
+        let intensities_at = |t: f64| match interpolate_photometric_webs(&web_a, &web_b, t) {
+            Ok(web) => web
+                .planes()
+                .iter()
+                .flat_map(|pl| pl.intensities().to_vec())
+                .collect::<Vec<f64>>(),
+            Err(e) => panic!("Interpolation error: {}", e),
+        };
+
+        assert_eq!(intensities_at(0.0), vec![3.0; 6]);
+        assert_eq!(intensities_at(1.0), vec![1.0; 6]);
+        assert_eq!(intensities_at(0.5), vec![2.0; 6]);
+
+        // The blend factor is clamped, rather than extrapolating.
+        assert_eq!(intensities_at(2.0), vec![1.0; 6]);
+    }
+
+    /// Check that webs with fewer intensities than angles are rejected, rather than panicking.
+    #[test]
+    fn test_combine_ragged_intensities() {
+        let web_a = constant_web(1.0);
+        let mut web_b = constant_web(1.0);
+        web_b.mut_planes()[1].set_intensities(vec![1.0; 2]);
+        assert!(matches!(
+            sum_photometric_web_intensities(vec![&web_a, &web_b]),
+            Err(Error::InconsistentIntensitiesInPlane(3, 2))
+        ));
+    }
+
+    #[test]
+    fn test_average_auto() {
+        // Two webs with different C-planes, and disjoint gamma sampling.
+        let web_from_angles = |c_angles: &[f64], gammas: Vec<f64>, intensity: f64| {
+            let planes = c_angles
+                .iter()
+                .map(|angle| {
+                    let mut plane = Plane::new();
+                    plane.set_angle_degrees(*angle);
+                    plane.set_angles_degrees(&gammas);
+                    plane.set_intensities(vec![intensity; gammas.len()]);
+                    plane
+                })
+                .collect();
+            let mut web = PhotometricWeb::new();
+            web.set_planes(planes);
+            web
+        };
+        let web_a = web_from_angles(
+            &[0.0, 90.0, 180.0, 270.0],
+            (0..181).step_by(20).map(|ang| ang as f64).collect(),
+            1.0,
+        );
+        let web_b = web_from_angles(
+            &[0.0, 180.0],
+            (10..171).step_by(20).map(|ang| ang as f64).collect(),
+            3.0,
+        );
+
+        match average_auto(vec![&web_a, &web_b]) {
+            Ok(average) => {
+                assert_eq!(average.n_planes(), 4);
+                for pl in average.planes() {
+                    assert_eq!(pl.n_samples(), 19);
+                    assert!(pl.intensities().iter().all(|intens| *intens == 2.0));
+                }
+            }
+            Err(e) => panic!("Average error: {}", e),
+        }
+
+        assert!(matches!(average_auto(vec![]), Err(Error::NoPlanes)));
+    }
+}
@@ -0,0 +1,14 @@
+/// The IES cutoff classification of a luminaire, describing how much of its light is emitted
+/// at and above the horizontal. Each class limits the intensity at and above 90 degrees, and at and
+/// above 80 degrees, as a fraction of the rated lamp lumens.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CutoffClass {
+    /// No intensity at or above 90 degrees, and no more than 10% at or above 80 degrees.
+    FullCutoff,
+    /// No more than 2.5% at or above 90 degrees, and no more than 10% at or above 80 degrees.
+    Cutoff,
+    /// No more than 5% at or above 90 degrees, and no more than 20% at or above 80 degrees.
+    SemiCutoff,
+    /// The intensity is not limited at or above the horizontal.
+    NonCutoff,
+}
@@ -3,8 +3,8 @@ use std::f64::consts::{FRAC_PI_2, PI};
 
 /// A utility function which mirrors the first quadrant of planes in a vector into the second quadrant.
 /// This means that we start with 0 -> \pi / 2 filled, and we return 0 -> \pi filled.
-pub fn mirror_first_quadrant(planes: &Vec<Plane>) -> Vec<Plane> {
-    let mut ret_planes = planes.clone();
+pub fn mirror_first_quadrant(planes: &[Plane]) -> Vec<Plane> {
+    let mut ret_planes = planes.to_vec();
     ret_planes.extend(planes.iter().rev().skip(1).map(|pl| {
         let mut newpl = pl.clone();
         *newpl.mut_angle() = (FRAC_PI_2 - pl.angle()) + FRAC_PI_2;
@@ -16,13 +16,13 @@ pub fn mirror_first_quadrant(planes: &Vec<Plane>) -> Vec<Plane> {
 
 /// A utility function which mirrors the first quadrant of planes in a vector into the second quadrant.
 /// This means that we start with 0 -> \pi filled, and we return 0 -> 2 \pi filled.
-pub fn mirror_first_hemisphere(planes: &Vec<Plane>) -> Vec<Plane> {
-    let mut ret_planes = planes.clone();
-    let take_planes = planes.iter().count() - 2;
+pub fn mirror_first_hemisphere(planes: &[Plane]) -> Vec<Plane> {
+    let mut ret_planes = planes.to_vec();
+    let take_planes = planes.len() - 2;
 
     ret_planes.extend(
         planes
-            .into_iter()
+            .iter()
             .rev()
             .skip(1)
             .take(take_planes)
@@ -38,8 +38,8 @@ pub fn mirror_first_hemisphere(planes: &Vec<Plane>) -> Vec<Plane> {
 
 /// A utility function which takes the hemisphere occupying the second and third quadrants (90 degree - 270 degree)
 /// and mirrors this onto the opposing (270 degree -> 90 degree) hemisphere. 
-pub fn mirror_second_and_third_quadrants(planes: &Vec<Plane>) -> Vec<Plane> {
-    let half = planes.iter().count() / 2;
+pub fn mirror_second_and_third_quadrants(planes: &[Plane]) -> Vec<Plane> {
+    let half = planes.len() / 2;
 
     // Assemble the first quadrant from the data we have. 
     let mut ret_planes: Vec<Plane> = planes
@@ -55,7 +55,7 @@ pub fn mirror_second_and_third_quadrants(planes: &Vec<Plane>) -> Vec<Plane> {
     .collect();
 
     // Now copy the 90 - 270 degree planes. 
-    ret_planes.extend(planes.iter().map(|pl| pl.clone()));
+    ret_planes.extend(planes.iter().cloned());
 
     let tmp_planes = planes
         .iter()
@@ -67,7 +67,7 @@ pub fn mirror_second_and_third_quadrants(planes: &Vec<Plane>) -> Vec<Plane> {
             *new_plane.mut_angle() = pl.angle() + 2.0 * (3.0 * FRAC_PI_2 - pl.angle());
             new_plane
         });
-    ret_planes.extend(tmp_planes.into_iter());
+    ret_planes.extend(tmp_planes);
 
     ret_planes
 
@@ -0,0 +1,84 @@
+//! Heatmap export of the intensity distribution, enabled by the `image` feature.
+
+use super::PhotometricWeb;
+use crate::err::Error;
+use image::{codecs::png::PngEncoder, ExtendedColorType, ImageEncoder};
+use std::io::Write;
+
+/// The colours (RGB) that the normalised intensities are mapped between, evenly spaced from zero to the peak.
+const COLORMAP_STOPS: [[f64; 3]; 5] = [
+    [0.0, 0.0, 0.0],
+    [60.0, 15.0, 140.0],
+    [200.0, 40.0, 80.0],
+    [250.0, 150.0, 20.0],
+    [255.0, 255.0, 200.0],
+];
+
+impl PhotometricWeb {
+    /// Writes a PNG heatmap of the intensity distribution to the given writer, `width` by `height` pixels in size.
+    /// The C-angles run from 0 to 2 pi across the image and the gamma angles from 0 (top) to pi (bottom), sampled
+    /// with `to_grid`. Intensities are normalised to the peak intensity of the web before being mapped to colours,
+    /// so a web without any positive intensity is drawn entirely in the lowest colour.
+    pub fn to_heatmap_png<W: Write>(
+        &self,
+        w: &mut W,
+        width: usize,
+        height: usize,
+    ) -> Result<(), Error> {
+        let peak = self.peak_intensity();
+        let grid = self.to_grid(width, height);
+        let pixels: Vec<u8> = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .flat_map(|(x, y)| {
+                let value = if peak > 0.0 { grid[x][y] / peak } else { 0.0 };
+                colormap(value)
+            })
+            .collect();
+
+        PngEncoder::new(w).write_image(
+            &pixels,
+            width as u32,
+            height as u32,
+            ExtendedColorType::Rgb8,
+        )?;
+        Ok(())
+    }
+}
+
+/// Maps a normalised intensity, clamped to [0, 1], to an RGB colour by interpolating between `COLORMAP_STOPS`.
+fn colormap(value: f64) -> [u8; 3] {
+    let scaled = value.clamp(0.0, 1.0) * (COLORMAP_STOPS.len() - 1) as f64;
+    let lower = (scaled.floor() as usize).min(COLORMAP_STOPS.len() - 2);
+    let frac = scaled - lower as f64;
+    let (from, to) = (COLORMAP_STOPS[lower], COLORMAP_STOPS[lower + 1]);
+    [0, 1, 2].map(|i| (from[i] + frac * (to[i] - from[i])).round() as u8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{colormap, COLORMAP_STOPS};
+    use crate::photweb::{PhotometricWeb, Plane};
+
+    /// Check that the heatmap is written as a PNG image.
+    #[test]
+    fn test_to_heatmap_png() {
+        let mut plane = Plane::new();
+        plane.set_angles_degrees(&[0.0, 90.0, 180.0]);
+        plane.set_intensities(vec![100.0, 50.0, 0.0]);
+        let mut web = PhotometricWeb::new();
+        web.set_planes(vec![plane]);
+
+        let mut buf: Vec<u8> = Vec::new();
+        web.to_heatmap_png(&mut buf, 32, 16).unwrap();
+        assert!(buf.starts_with(&[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A]));
+    }
+
+    /// The ends of the colour map should be the first and last stops.
+    #[test]
+    fn test_colormap() {
+        assert_eq!(colormap(-1.0), COLORMAP_STOPS[0].map(|c| c as u8));
+        assert_eq!(colormap(0.0), COLORMAP_STOPS[0].map(|c| c as u8));
+        assert_eq!(colormap(1.0), COLORMAP_STOPS[4].map(|c| c as u8));
+        assert_eq!(colormap(2.0), COLORMAP_STOPS[4].map(|c| c as u8));
+    }
+}
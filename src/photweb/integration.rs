@@ -0,0 +1,11 @@
+/// The numerical rule used to integrate the intensities of a plane over its gamma angles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IntegrationMethod {
+    /// Each sample is weighted by the width of the cell around it, as given by `Plane::delta_angle`.
+    /// This is the method used by `Plane::integrate_intensity`.
+    #[default]
+    Midpoint,
+    /// The trapezoidal rule, where each interval between two samples is weighted by the mean of the
+    /// values at either end, so the integration does not extend beyond the first and last samples.
+    Trapezoidal,
+}
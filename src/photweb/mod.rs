@@ -8,8 +8,12 @@ pub mod photweb_writer;
 pub use self::{photweb_reader::*, photweb_writer::*};
 
 // Structs
+pub mod cutoff;
+pub mod integration;
+#[allow(clippy::module_inception)]
 pub mod photweb;
 pub mod plane;
+pub mod report;
 pub mod units;
 
 // Builders
@@ -18,7 +22,14 @@ pub mod photweb_builder;
 // Module functions.
 mod funcs;
 
-pub use self::{funcs::*, photweb::*, photweb_builder::*, plane::*, units::*};
+// Optional exporters.
+#[cfg(feature = "image")]
+mod heatmap;
+
+pub use self::{
+    cutoff::*, funcs::*, integration::*, photweb::*, photweb_builder::*, plane::*, report::*,
+    units::*,
+};
 
 #[cfg(test)]
 mod tests;
@@ -1,7 +1,46 @@
-use super::{Plane, PlaneWidth};
+use super::{
+    CutoffClass, IntegrationMethod, IntensityUnits, PhotometricReport, PhotometricWebBuilder,
+    PhotometricWebWriter, Plane, PlaneWidth,
+};
 use property::Property;
-use std::{default::Default, f64::consts::PI};
-use crate::util::geom::angle_difference;
+use std::{
+    default::Default,
+    f64::consts::{FRAC_PI_2, PI},
+    path::Path,
+};
+use crate::{
+    io::{
+        eulumdat::{EulumdatFile, EulumdatSymmetry},
+        ies::{lum_opening::IesLuminousOpening, IesFile},
+    },
+    ops::err::Error,
+    util::geom::{angle_difference, degrees_to_radians, radians_to_degrees},
+};
+
+/// The default number of evenly spaced C-planes used when revolving a web around the vertical axis,
+/// such as when expanding a spherically symmetric web with `PhotometricWeb::resample_azimuth`.
+pub const DEFAULT_AZIMUTH_STEPS: usize = 36;
+/// The tolerance (in radians) used when matching plane angles against one another.
+const PLANE_ANGLE_TOLERANCE: f64 = 1E-6;
+/// The tolerance used when checking that intensities satisfy a symmetry before collapsing it.
+const SYMMETRY_TOLERANCE: f64 = 1E-6;
+/// The fraction of the total flux below which the flux-weighted mean direction is considered undefined.
+const CENTROID_TOLERANCE: f64 = 1E-9;
+/// The projected area (m^2) below which the luminous opening is considered to not be visible.
+const MIN_PROJECTED_AREA: f64 = 1E-12;
+/// The (A, B) constants of the zonal multipliers, K = exp(-A * RCR^B), for each 10 degree zone from
+/// 0 -> 90 degrees, as used by the zonal cavity method.
+const ZONAL_MULTIPLIER_CONSTANTS: [(f64, f64); 9] = [
+    (0.0, 0.0),
+    (0.041, 0.98),
+    (0.07, 1.05),
+    (0.1, 1.12),
+    (0.136, 1.16),
+    (0.19, 1.25),
+    (0.315, 1.25),
+    (0.64, 1.25),
+    (2.1, 0.8),
+];
 
 #[derive(Default, Debug, Property)]
 #[property(get(public), set(public))]
@@ -10,6 +49,15 @@ pub struct PhotometricWeb {
     /// Note: if we have a single element in this vector, if is assumed spherically symmetric.
     #[property(set(disable))]
     planes: Vec<Plane>,
+    /// The input power of the luminaire (watts), if provided by the source file.
+    input_watts: Option<f64>,
+    /// The total rated luminous flux of the lamps (lumens), if provided by the source file.
+    total_rated_lumens: Option<f64>,
+    /// The geometry of the luminous opening, with dimensions in metres, if provided by the source file.
+    luminous_opening: Option<IesLuminousOpening>,
+    /// The heights (metres) of the luminous area in the C0, C90, C180 and C270 directions, if provided by the
+    /// source file. These can differ by direction, such as for the sides of a linear luminaire.
+    luminous_area_heights: Option<[f64; 4]>,
 }
 
 impl PhotometricWeb {
@@ -20,23 +68,129 @@ impl PhotometricWeb {
         }
     }
 
-    /// Set the
+    /// Creates a photometric web from the given planes, checking that the web is valid as described in `validate`.
+    /// Unlike `set_planes`, an error is returned rather than creating an inconsistent web.
+    pub fn try_from_planes(planes: Vec<Plane>) -> Result<PhotometricWeb, Error> {
+        let mut web = PhotometricWeb::new();
+        web.set_planes(planes);
+        web.validate()?;
+        Ok(web)
+    }
+
+    /// Reads a photometric web from a file, choosing the parser from the file extension.
+    /// This is a shortcut for `PhotometricWebBuilder::from_file(path).build()`.
+    pub fn from_path(path: &Path) -> Result<PhotometricWeb, crate::err::Error> {
+        PhotometricWebBuilder::from_file(path).build()
+    }
+
+    /// Writes the photometric web to a file, choosing the writer from the file extension:
+    /// `.ies` files are written as IES, and `.ldt` or `.eul` files as EULUMDAT.
+    /// Any other extension returns an `Error::InvalidFileType` error.
+    pub fn write_file(&self, path: &Path) -> Result<(), crate::err::Error> {
+        let file_ext = path
+            .extension()
+            .and_then(|file_ext| file_ext.to_str())
+            .unwrap_or_default();
+        match file_ext.to_lowercase().as_str() {
+            "ies" => IesFile::write(self, path),
+            "ldt" | "eul" => EulumdatFile::write(self, path),
+            _ => Err(crate::err::Error::InvalidFileType(file_ext.to_owned())),
+        }
+    }
+
+    /// Set the planes of the photometric web. The plane angles are normalised into [0, 2 pi) and sorted,
+    /// as described in `normalize_angles`.
     pub fn set_planes(&mut self, planes: Vec<Plane>) {
         self.planes = planes;
+        self.normalize_angles();
+    }
+
+    /// Maps the angle of each plane into the range [0, 2 pi) and re-sorts the planes by angle,
+    /// updating the widths of the planes to match their new neighbours.
+    /// Angles within `PLANE_ANGLE_TOLERANCE` of 2 pi are wrapped to zero.
+    pub fn normalize_angles(&mut self) {
+        for plane in self.planes.iter_mut() {
+            let angle = plane.angle().rem_euclid(2.0 * PI);
+            plane.set_angle(if 2.0 * PI - angle < PLANE_ANGLE_TOLERANCE {
+                0.0
+            } else {
+                angle
+            });
+        }
+        self.planes
+            .sort_by(|pl1, pl2| pl1.angle().total_cmp(&pl2.angle()));
+
         for iplane in 0..self.n_planes() {
             let delta_angle = self.delta_angle(iplane);
             self.planes[iplane].set_width(delta_angle);
         }
     }
 
+    /// Removes all of the planes from the photometric web, keeping the allocated storage
+    /// so that the web can be refilled without being recreated.
+    pub fn clear(&mut self) {
+        self.planes.clear();
+    }
+
+    /// Flips the gamma convention of every plane in the web, as described in `Plane::flip_gamma`.
+    /// This corrects files that measure gamma from zenith rather than nadir, turning a downlight into an uplight.
+    pub fn flip_gamma(&mut self) {
+        self.planes.iter_mut().for_each(|pl| pl.flip_gamma());
+    }
+
+    /// Reflects every plane of the web about the given azimuthal axis (in radians), so that a plane at
+    /// `axis + delta` moves to `axis - delta`, such as when a luminaire is installed flipped left-to-right.
+    /// Each plane keeps its intensities, and the planes are re-sorted as described in `normalize_angles`.
+    pub fn mirror_azimuth(&mut self, axis_rad: f64) {
+        for plane in self.planes.iter_mut() {
+            plane.set_angle(2.0 * axis_rad - plane.angle());
+        }
+        self.normalize_angles();
+    }
+
+    /// Zeroes the intensities beyond the given gamma angle (in radians) in each of the planes, as described in
+    /// `Plane::apply_gamma_cutoff`. The flux outside of the cone is removed from the web.
+    pub fn apply_gamma_cutoff(&mut self, max_gamma_rad: f64) {
+        self.planes
+            .iter_mut()
+            .for_each(|pl| pl.apply_gamma_cutoff(max_gamma_rad));
+    }
+
+    /// Repairs gamma angles that are not strictly increasing due to rounding in each of the planes, as described in
+    /// `Plane::repair_monotonic`.
+    pub fn repair_monotonic(&mut self, tolerance: f64) {
+        self.planes
+            .iter_mut()
+            .for_each(|pl| pl.repair_monotonic(tolerance));
+    }
+
+    /// Sets any negative intensities in each of the planes to zero, as described in `Plane::clamp_negative`.
+    pub fn clamp_negative(&mut self) {
+        self.planes.iter_mut().for_each(|pl| pl.clamp_negative());
+    }
+
     /// Returns the number of planes in the photometric web.
     pub fn n_planes(&self) -> usize {
-        self.planes.iter().count()
+        self.planes.len()
+    }
+
+    /// Returns the C-angle (in radians) of each of the planes in the web, in order.
+    pub fn c_angles(&self) -> Vec<f64> {
+        self.planes.iter().map(|pl| pl.angle()).collect()
+    }
+
+    /// Returns the gamma angles (in radians) of the web. The planes are assumed to share the same gamma angles,
+    /// so the angles of the first plane are returned, or an empty vector for an empty web.
+    pub fn gamma_angles(&self) -> Vec<f64> {
+        self.planes
+            .first()
+            .map(|pl| pl.angles().to_vec())
+            .unwrap_or_default()
     }
 
     /// Is this a full web, or spherically symmetric?
     pub fn is_spherically_symmetric(&self) -> bool {
-        self.planes.iter().count() == 1
+        self.planes.len() == 1
     }
 
     /// The dphi for a given plane - used during integration.
@@ -62,6 +216,33 @@ impl PhotometricWeb {
         }
     }
 
+    /// Checks whether two photometric webs are equal to within `epsilon`, comparing the number of planes,
+    /// and the angles and intensities of each plane.
+    pub fn approx_eq(&self, other: &PhotometricWeb, epsilon: f64) -> bool {
+        self.n_planes() == other.n_planes()
+            && self
+                .planes
+                .iter()
+                .zip(other.planes.iter())
+                .all(|(pl1, pl2)| pl1.approx_eq(pl2, epsilon))
+    }
+
+    /// Returns the intensity of the given sample in the given plane, or `None` if either index is out of range.
+    pub fn intensity(&self, plane: usize, sample: usize) -> Option<f64> {
+        self.planes
+            .get(plane)
+            .and_then(|pl| pl.intensities().get(sample))
+            .copied()
+    }
+
+    /// Returns an iterator over every (C-angle (radians), gamma angle (radians), intensity) sample in the web.
+    pub fn iter_samples(&self) -> impl Iterator<Item = (f64, f64, f64)> + '_ {
+        self.planes.iter().flat_map(|pl| {
+            pl.iter_samples()
+                .map(move |(gamma, intensity)| (pl.angle(), gamma, intensity))
+        })
+    }
+
     /// Integrates the total energy coming from the intensity distribution.
     /// This makes use of the integration that is a part of the planes.
     pub fn total_intensity(&self) -> f64 {
@@ -71,6 +252,559 @@ impl PhotometricWeb {
             .sum()
     }
 
+    /// Returns the contribution of each plane to `total_intensity`, in the same order as the planes.
+    /// Each plane is integrated over its azimuthal width, so the contributions sum to the total.
+    pub fn plane_fluxes(&self) -> Vec<f64> {
+        self.planes
+            .iter()
+            .map(|p| p.integrate_intensity())
+            .collect()
+    }
+
+    /// Returns the index of the plane that contributes the most to `total_intensity`, as given by `plane_fluxes`.
+    /// If several planes contribute equally, the first is returned. An empty web returns `None`.
+    pub fn dominant_plane(&self) -> Option<usize> {
+        self.plane_fluxes()
+            .iter()
+            .enumerate()
+            .fold(
+                None,
+                |best: Option<(usize, f64)>, (iplane, flux)| match best {
+                    Some((_, best_flux)) if best_flux >= *flux => best,
+                    _ => Some((iplane, *flux)),
+                },
+            )
+            .map(|(iplane, _)| iplane)
+    }
+
+    /// Integrates the total energy coming from the intensity distribution, using the given integration method
+    /// for each plane, as described in `Plane::integrate_intensity_with`.
+    pub fn total_intensity_with(&self, method: IntegrationMethod) -> f64 {
+        self.planes
+            .iter()
+            .map(|p| p.integrate_intensity_with(method))
+            .sum()
+    }
+
+    /// Integrates the total flux coming from the intensity distribution over the measured gamma range of each plane.
+    /// Unlike `total_intensity`, the integration does not extend beyond the first and last gamma angles of each plane,
+    /// so a distribution only measured over 0 -> 90 degrees yields the flux in the lower hemisphere.
+    pub fn total_flux(&self) -> f64 {
+        self.planes
+            .iter()
+            .map(|p| match (p.angles().first(), p.angles().last()) {
+                (Some(first), Some(last)) => p.integrate_intensity_range(*first, *last),
+                _ => 0.0,
+            })
+            .sum()
+    }
+
+    /// Integrates the total flux (lumens) coming from the intensity distribution, as described in `total_flux`.
+    /// Integrating over solid angle only yields lumens for intensities in candela, so an `IncompatibleUnits`
+    /// error is returned if any of the planes are stored in other units.
+    pub fn total_flux_lumens(&self) -> Result<f64, Error> {
+        match self
+            .planes
+            .iter()
+            .position(|pl| *pl.units() != IntensityUnits::Candela)
+        {
+            Some(iplane) => Err(Error::IncompatibleUnits(iplane)),
+            None => Ok(self.total_flux()),
+        }
+    }
+
+    /// Returns the solid angle (in steradians) spanned by the measured C-plane and gamma ranges.
+    /// Each plane covers its width in C and the range between its first and last gamma angles,
+    /// so a web measured over the full sphere returns 4 pi, and one measured from 0 -> 90 degrees
+    /// in gamma returns 2 pi.
+    pub fn measured_solid_angle(&self) -> f64 {
+        self.planes
+            .iter()
+            .map(|p| match (p.angles().first(), p.angles().last()) {
+                (Some(first), Some(last)) => p.width().total() * (first.cos() - last.cos()),
+                _ => 0.0,
+            })
+            .sum()
+    }
+
+    /// Returns the C-angle (in radians, between 0 and 2 pi) indicating the dominant azimuthal direction of the flux.
+    /// This is the circular mean of the plane angles, weighted by the integrated flux of each plane.
+    /// If the flux has no dominant azimuthal direction, such as for spherically symmetric or balanced
+    /// distributions, the mean is undefined and `f64::NAN` is returned.
+    pub fn flux_centroid_cangle(&self) -> f64 {
+        if self.is_spherically_symmetric() {
+            return f64::NAN;
+        }
+
+        let (sum_sin, sum_cos, sum_flux) =
+            self.planes
+                .iter()
+                .fold((0.0, 0.0, 0.0), |(sum_sin, sum_cos, sum_flux), pl| {
+                    let flux = pl.integrate_intensity();
+                    (
+                        sum_sin + flux * pl.angle().sin(),
+                        sum_cos + flux * pl.angle().cos(),
+                        sum_flux + flux.abs(),
+                    )
+                });
+
+        // If the resultant vector is negligible compared to the flux, there is no dominant direction.
+        if sum_sin.hypot(sum_cos) <= CENTROID_TOLERANCE * sum_flux || sum_flux == 0.0 {
+            return f64::NAN;
+        }
+        sum_sin.atan2(sum_cos).rem_euclid(2.0 * PI)
+    }
+
+    /// Returns the direction of the flux-weighted centroid of the distribution, as (C-angle, gamma) in radians.
+    /// The unit direction vector of each sample is weighted by the flux it contributes, and the sum of these
+    /// is converted back to spherical angles, with gamma = 0 pointing straight down. If the distribution emits
+    /// no flux the direction is undefined, and (0.0, 0.0) is returned. Likewise, the C-angle is returned as zero
+    /// if the centroid lies on the vertical axis.
+    pub fn centroid_direction(&self) -> (f64, f64) {
+        let spherically_symmetric = self.is_spherically_symmetric();
+        let (sum_x, sum_y, sum_z) = self
+            .planes
+            .iter()
+            .flat_map(|pl| {
+                pl.iter_samples()
+                    .enumerate()
+                    .map(move |(i, (gamma, intens))| {
+                        let flux = pl.width().total() * intens * gamma.sin() * pl.delta_angle(i);
+                        (pl.angle(), gamma, flux)
+                    })
+            })
+            .fold((0.0, 0.0, 0.0), |(x, y, z), (c_angle, gamma, flux)| {
+                // A spherically symmetric plane spans every C-angle, so the horizontal components cancel.
+                let horizontal = if spherically_symmetric {
+                    0.0
+                } else {
+                    flux * gamma.sin()
+                };
+                (
+                    x + horizontal * c_angle.cos(),
+                    y + horizontal * c_angle.sin(),
+                    z + flux * gamma.cos(),
+                )
+            });
+
+        let horizontal = sum_x.hypot(sum_y);
+        if horizontal == 0.0 && sum_z == 0.0 {
+            return (0.0, 0.0);
+        }
+        let c_angle = if horizontal > 0.0 {
+            sum_y.atan2(sum_x).rem_euclid(2.0 * PI)
+        } else {
+            0.0
+        };
+        (c_angle, horizontal.atan2(sum_z))
+    }
+
+    /// Integrates the energy emitted into the zone between two gamma angles (in radians).
+    /// This makes use of the partial integration that is a part of the planes.
+    pub fn zonal_flux(&self, gamma_lower_rad: f64, gamma_upper_rad: f64) -> f64 {
+        self.planes
+            .iter()
+            .map(|p| p.integrate_intensity_range(gamma_lower_rad, gamma_upper_rad))
+            .sum()
+    }
+
+    /// Integrates the energy emitted into the region bounded by two C-angles and two gamma angles (in radians).
+    /// The region runs anticlockwise from `c_min_rad` to `c_max_rad`, wrapping around 2 pi, so a sector can span C0.
+    /// Each plane contributes the fraction of its azimuthal width that lies within the region, and the gamma bounds
+    /// are handled as in `zonal_flux`. A C-angle range of 2 pi or more covers every plane.
+    pub fn flux_in_region(
+        &self,
+        c_min_rad: f64,
+        c_max_rad: f64,
+        gamma_min_rad: f64,
+        gamma_max_rad: f64,
+    ) -> f64 {
+        let span = if c_max_rad - c_min_rad >= 2.0 * PI {
+            2.0 * PI
+        } else {
+            (c_max_rad - c_min_rad).rem_euclid(2.0 * PI)
+        };
+
+        self.planes
+            .iter()
+            .map(|pl| {
+                let width = pl.width().total();
+                if width <= 0.0 {
+                    return 0.0;
+                }
+                // The plane's azimuthal cell relative to the start of the region, which may overlap
+                // both the region and its copy one revolution later.
+                let start = (pl.angle() - pl.width().lower() - c_min_rad).rem_euclid(2.0 * PI);
+                let end = start + width;
+                let overlap = (end.min(span) - start).max(0.0)
+                    + (end.min(2.0 * PI + span) - start.max(2.0 * PI)).max(0.0);
+                pl.integrate_intensity_range(gamma_min_rad, gamma_max_rad) * overlap / width
+            })
+            .sum()
+    }
+
+    /// Returns the coefficient of utilisation of the luminaire in a room with the given room cavity ratio, calculated
+    /// using the IES zonal cavity method. The reflectances are given as (ceiling cavity, wall, floor cavity) effective
+    /// reflectances, each between 0 and 1. The flux in each zone is taken relative to the rated lumens of the web,
+    /// falling back to the total flux of the distribution if these are unknown. A web without any flux returns 0.0.
+    pub fn zonal_cavity_cu(&self, room_cavity_ratio: f64, reflectances: (f64, f64, f64)) -> f64 {
+        let (rho_cc, rho_w, rho_fc) = reflectances;
+        let rcr = room_cavity_ratio.max(0.0);
+        let lumens = self
+            .total_rated_lumens
+            .filter(|lumens| *lumens > 0.0)
+            .unwrap_or_else(|| self.total_flux());
+        if lumens <= 0.0 {
+            return 0.0;
+        }
+
+        // The fraction of the lamp lumens emitted downwards and upwards, and the direct ratio of the
+        // downward flux that falls directly onto the work plane.
+        let (downward, upward) = self.hemisphere_flux();
+        let (phi_d, phi_u) = (downward / lumens, upward / lumens);
+        let direct_ratio = if downward > 0.0 {
+            ZONAL_MULTIPLIER_CONSTANTS
+                .iter()
+                .enumerate()
+                .map(|(izone, (a, b))| {
+                    let zone_flux = self.zonal_flux(
+                        degrees_to_radians(10.0 * izone as f64),
+                        degrees_to_radians(10.0 * (izone + 1) as f64),
+                    );
+                    (-a * rcr.powf(*b)).exp() * zone_flux
+                })
+                .sum::<f64>()
+                / downward
+        } else {
+            0.0
+        };
+
+        // The form factor between the floor and ceiling cavities, and the cavity coefficients.
+        let f_2 = (rcr * rcr + 1.0).sqrt() - rcr;
+        let c_1 = if rcr > 0.0 {
+            (1.0 - rho_w) * (1.0 - f_2 * f_2) * rcr
+                / (2.5 * rho_w * (1.0 - f_2 * f_2) + rcr * f_2 * (1.0 - rho_w))
+        } else {
+            0.0
+        };
+        let c_2 = (1.0 - rho_cc) * (1.0 + f_2) / (1.0 + rho_cc * f_2);
+        let c_3 = (1.0 - rho_fc) * (1.0 + f_2) / (1.0 + rho_fc * f_2);
+        let c_0 = c_1 + c_2 + c_3;
+
+        // The flux reaching the work plane via the walls, via the ceiling cavity, and directly.
+        let wall_term = if rcr > 0.0 {
+            2.5 * rho_w * c_1 * c_3 * (1.0 - direct_ratio) * phi_d
+                / (rcr * (1.0 - rho_w) * (1.0 - rho_fc) * c_0)
+        } else {
+            0.0
+        };
+        let ceiling_term = rho_cc * c_2 * c_3 * phi_u / ((1.0 - rho_cc) * (1.0 - rho_fc) * c_0);
+        let direct_term =
+            (1.0 - rho_fc * c_3 * (c_1 + c_2) / ((1.0 - rho_fc) * c_0)) * direct_ratio * phi_d
+                / (1.0 - rho_fc);
+        wall_term + ceiling_term + direct_term
+    }
+
+    /// Returns the flux emitted into the lower (gamma 0 -> 90 degrees) and upper (gamma 90 -> 180 degrees)
+    /// hemispheres, as a `(downward, upward)` pair.
+    pub fn hemisphere_flux(&self) -> (f64, f64) {
+        (self.zonal_flux(0.0, 0.5 * PI), self.zonal_flux(0.5 * PI, PI))
+    }
+
+    /// Returns the flux delivered into the zone between two gamma angles (in degrees) per watt of input power.
+    /// This requires the input power to be known, so `None` is returned if it is missing or is not positive.
+    pub fn zone_efficacy(&self, gamma_lower_deg: f64, gamma_upper_deg: f64) -> Option<f64> {
+        match self.input_watts {
+            Some(watts) if watts > 0.0 => Some(
+                self.zonal_flux(
+                    degrees_to_radians(gamma_lower_deg),
+                    degrees_to_radians(gamma_upper_deg),
+                ) / watts,
+            ),
+            _ => None,
+        }
+    }
+
+    /// Returns the light output ratio (LOR) of the luminaire: the total flux emitted by the luminaire
+    /// divided by the rated flux of its lamps. This assumes that the intensities are stored in candela.
+    /// This requires the rated lumens to be known, so `None` is returned if they are missing or are not positive.
+    pub fn light_output_ratio(&self) -> Option<f64> {
+        match self.total_rated_lumens {
+            Some(lumens) if lumens > 0.0 => Some(self.total_flux() / lumens),
+            _ => None,
+        }
+    }
+
+    /// Returns the intensity in the given direction, specified by a C-angle and gamma angle (in radians).
+    /// The intensity is interpolated linearly in gamma within the planes either side of the C-angle,
+    /// and then linearly in C between them, wrapping around 2 pi. If the web has no planes, zero is returned.
+    pub fn intensity_at(&self, c_angle_rad: f64, gamma_rad: f64) -> f64 {
+        let (lower, upper) = match self.bracketing_planes(c_angle_rad) {
+            Some(planes) => planes,
+            None => return 0.0,
+        };
+        let span = (upper.angle() - lower.angle()).rem_euclid(2.0 * PI);
+        if span == 0.0 {
+            return lower.intensity_at(gamma_rad);
+        }
+
+        let frac = (c_angle_rad - lower.angle()).rem_euclid(2.0 * PI) / span;
+        let lower_intens = lower.intensity_at(gamma_rad);
+        lower_intens + frac * (upper.intensity_at(gamma_rad) - lower_intens)
+    }
+
+    /// Returns the average luminance (cd/m^2) of the luminaire in the direction given by a C-angle and gamma angle
+    /// (in radians): the interpolated intensity divided by the area of the luminous opening projected in that direction.
+    /// If the heights of the luminous area are known, the sides of a flat rectangular or circular opening are included
+    /// in the projected area, using the height in that direction from `luminous_area_height_at`.
+    /// This assumes that the intensities are stored in candela. `None` is returned if the web has no luminous opening,
+    /// or if the opening has no projected area in that direction.
+    pub fn average_luminance(&self, c_angle_rad: f64, gamma_rad: f64) -> Option<f64> {
+        let opening = self.luminous_opening.as_ref()?;
+        let height = self.luminous_area_height_at(c_angle_rad).unwrap_or(0.0);
+        let side_area = height
+            * gamma_rad.sin().abs()
+            * match *opening {
+                IesLuminousOpening::Rectangular { width, length } => {
+                    width * c_angle_rad.cos().abs() + length * c_angle_rad.sin().abs()
+                }
+                IesLuminousOpening::Circular { diameter } => diameter,
+                _ => 0.0,
+            };
+        match opening
+            .projected_area(c_angle_rad, gamma_rad)
+            .map(|area| area + side_area)
+        {
+            Some(area) if area > MIN_PROJECTED_AREA => {
+                Some(self.intensity_at(c_angle_rad, gamma_rad) / area)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the height (metres) of the luminous area in the direction of the given C-angle (in radians), interpolating
+    /// linearly between the C0, C90, C180 and C270 heights and wrapping around 2 pi. `None` is returned if the heights
+    /// of the luminous area are not known.
+    pub fn luminous_area_height_at(&self, c_angle_rad: f64) -> Option<f64> {
+        let heights = self.luminous_area_heights?;
+        let quadrant = c_angle_rad.rem_euclid(2.0 * PI) / FRAC_PI_2;
+        let lower = (quadrant.floor() as usize).min(3);
+        let frac = quadrant - lower as f64;
+        Some(heights[lower] + frac * (heights[(lower + 1) % 4] - heights[lower]))
+    }
+
+    /// Returns the (C-angle, intensity) pair of every plane in the web at the given gamma angle (in radians),
+    /// interpolating between the samples of each plane. These are the intensities that would be seen by an
+    /// observer looking at the luminaire from that elevation, as required for glare (UGR) tables.
+    pub fn intensities_at_gamma(&self, gamma_rad: f64) -> Vec<(f64, f64)> {
+        self.planes
+            .iter()
+            .map(|pl| (pl.angle(), pl.intensity_at(gamma_rad)))
+            .collect()
+    }
+
+    /// Samples the distribution over a uniform grid of `n_c` C-angles and `n_gamma` gamma angles using `intensity_at`,
+    /// such as for drawing a heatmap. Each row of the grid is a C-angle, with the C-angles spaced evenly over [0, 2 pi)
+    /// and the gamma angles spanning [0, pi] inclusive. A single gamma angle is placed at nadir.
+    pub fn to_grid(&self, n_c: usize, n_gamma: usize) -> Vec<Vec<f64>> {
+        let gamma_step = if n_gamma > 1 {
+            PI / (n_gamma - 1) as f64
+        } else {
+            0.0
+        };
+        (0..n_c)
+            .map(|ic| {
+                let c_angle = 2.0 * PI * ic as f64 / n_c as f64;
+                (0..n_gamma)
+                    .map(|igamma| self.intensity_at(c_angle, igamma as f64 * gamma_step))
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Returns the IES cutoff classification of the distribution, found from the peak intensities at and
+    /// above 80 and 90 degrees as a fraction of the rated lamp lumens. This assumes that the intensities
+    /// are stored in candela. If the rated lumens are not known, the total flux of the web is used instead.
+    pub fn cutoff_classification(&self) -> CutoffClass {
+        let lumens = match self.total_rated_lumens {
+            Some(lumens) if lumens > 0.0 => lumens,
+            _ => self.total_flux(),
+        };
+        let fraction = |intens: f64| if intens <= 0.0 { 0.0 } else { intens / lumens };
+        let above_80 = fraction(self.peak_intensity_from(degrees_to_radians(80.0)));
+        let above_90 = fraction(self.peak_intensity_from(FRAC_PI_2));
+
+        if above_90 <= 0.0 && above_80 <= 0.1 {
+            CutoffClass::FullCutoff
+        } else if above_90 <= 0.025 && above_80 <= 0.1 {
+            CutoffClass::Cutoff
+        } else if above_90 <= 0.05 && above_80 <= 0.2 {
+            CutoffClass::SemiCutoff
+        } else {
+            CutoffClass::NonCutoff
+        }
+    }
+
+    /// Returns the spacing criterion (SC) of the plane nearest to the given C-angle (in radians), following the
+    /// IES method: the gamma angle at which the intensity falls to 50% of the nadir intensity is found, and the
+    /// SC is twice its tangent. This is the ratio of the maximum luminaire spacing to mounting height, and is
+    /// most commonly quoted for the C0 and C90 planes. If the intensity does not fall to 50% below the horizontal,
+    /// `f64::INFINITY` is returned, and if the web is empty or has no nadir intensity, `f64::NAN` is returned.
+    pub fn spacing_criterion(&self, c_angle_rad: f64) -> f64 {
+        let plane = match self.plane_at_angle(c_angle_rad) {
+            Some(plane) => plane,
+            None => return f64::NAN,
+        };
+        let nadir = plane.intensity_at(0.0);
+        if nadir <= 0.0 {
+            return f64::NAN;
+        }
+
+        let half_angle = Self::threshold_crossing_angle(plane, 0.5 * nadir);
+        if half_angle >= FRAC_PI_2 {
+            return f64::INFINITY;
+        }
+        2.0 * half_angle.tan()
+    }
+
+    /// Returns the peak intensity across all of the planes at or above the given gamma angle (in radians),
+    /// including the interpolated intensity at exactly that angle.
+    fn peak_intensity_from(&self, gamma_rad: f64) -> f64 {
+        self.planes
+            .iter()
+            .flat_map(|pl| {
+                pl.iter_samples()
+                    .filter(move |(gamma, _)| *gamma > gamma_rad)
+                    .map(|(_, intens)| intens)
+                    .chain(std::iter::once(pl.intensity_at(gamma_rad)))
+            })
+            .fold(0.0, f64::max)
+    }
+
+    /// Returns the smallest non-zero intensity in the web, and the direction it is found in, as
+    /// (C-angle, gamma angle, intensity) with the angles in radians, in the same order as `iter_samples`.
+    /// Zero and negative intensities are ignored, and (0.0, 0.0, 0.0) is returned if there are no positive intensities.
+    pub fn min_intensity(&self) -> (f64, f64, f64) {
+        self.iter_samples()
+            .filter(|(_, _, intens)| *intens > 0.0)
+            .min_by(|(_, _, intens1), (_, _, intens2)| intens1.total_cmp(intens2))
+            .unwrap_or((0.0, 0.0, 0.0))
+    }
+
+    /// Returns the dynamic range of the web: the ratio of the peak intensity to the smallest non-zero intensity,
+    /// as found by `min_intensity`. If there are no positive intensities, the ratio is undefined and NaN is returned.
+    pub fn dynamic_range(&self) -> f64 {
+        let (_, _, min_intensity) = self.min_intensity();
+        if min_intensity > 0.0 {
+            self.peak_intensity() / min_intensity
+        } else {
+            f64::NAN
+        }
+    }
+
+    /// Gathers the commonly reported metrics of the distribution into a single report, with the
+    /// light output ratio calculated relative to the provided rated lumens. This assumes that the
+    /// intensities are stored in candela.
+    pub fn photometric_report(&self, rated_lumens: f64) -> PhotometricReport {
+        let total_flux = self.total_flux();
+        let (downward_flux, upward_flux) = self.hemisphere_flux();
+        let hemisphere_flux = downward_flux + upward_flux;
+        let (peak_c_angle, peak_gamma, peak_intensity) = self
+            .iter_samples()
+            .max_by(|(_, _, intens1), (_, _, intens2)| intens1.total_cmp(intens2))
+            .unwrap_or((0.0, 0.0, 0.0));
+
+        PhotometricReport::new(
+            total_flux,
+            if rated_lumens > 0.0 {
+                Some(total_flux / rated_lumens)
+            } else {
+                None
+            },
+            if hemisphere_flux > 0.0 {
+                downward_flux / hemisphere_flux
+            } else {
+                0.0
+            },
+            downward_flux,
+            upward_flux,
+            peak_intensity,
+            (peak_c_angle, peak_gamma),
+            self.beam_angle(),
+        )
+    }
+
+    /// Returns the peak intensity across all of the planes in the web.
+    pub(crate) fn peak_intensity(&self) -> f64 {
+        self.planes
+            .iter()
+            .flat_map(|pl| pl.intensities().iter())
+            .fold(0.0, |peak, intens| f64::max(peak, *intens))
+    }
+
+    /// Returns the beam angle (in radians), as defined by NEMA: the full angle of the cone in which
+    /// the intensity is at least 50% of the peak intensity.
+    /// The beam is assumed to be centred on the gamma = 0 axis. For each plane the gamma angle at which
+    /// the intensity first falls below half of the peak is interpolated, and the beam angle is twice
+    /// the mean of these angles across the planes.
+    pub fn beam_angle(&self) -> f64 {
+        if self.n_planes() == 0 {
+            return 0.0;
+        }
+
+        let threshold = 0.5 * self.peak_intensity();
+        let sum_half_angles: f64 = self
+            .planes
+            .iter()
+            .map(|pl| Self::threshold_crossing_angle(pl, threshold))
+            .sum();
+
+        2.0 * sum_half_angles / self.n_planes() as f64
+    }
+
+    /// Returns the fraction of the total flux that is contained within the cone defined by the beam angle.
+    /// This is a measure of how efficiently the luminaire delivers light into its beam.
+    /// If the web emits no flux, zero is returned.
+    pub fn flux_within_beam(&self) -> f64 {
+        let total_flux = self.total_flux();
+        if total_flux <= 0.0 {
+            return 0.0;
+        }
+
+        self.zonal_flux(0.0, 0.5 * self.beam_angle()) / total_flux
+    }
+
+    /// Returns the effective solid angle of the beam (in steradians): the total flux divided by the peak intensity.
+    /// This is the solid angle that a uniform beam at the peak intensity would fill to emit the same flux.
+    /// If the web has no positive peak intensity, zero is returned.
+    pub fn beam_solid_angle(&self) -> f64 {
+        let peak = self.peak_intensity();
+        if peak <= 0.0 {
+            return 0.0;
+        }
+        self.total_intensity() / peak
+    }
+
+    /// Finds the gamma angle (in radians) at which the intensity in the plane first falls below the threshold,
+    /// interpolating linearly between samples. If the intensity never falls below the threshold, the last
+    /// gamma angle of the plane is returned, and if it never reaches the threshold zero is returned.
+    fn threshold_crossing_angle(plane: &Plane, threshold: f64) -> f64 {
+        let n_samples = plane.n_samples().min(plane.intensities().len());
+        let angles = &plane.angles()[..n_samples];
+        let intensities = &plane.intensities()[..n_samples];
+        match intensities.iter().position(|intens| *intens >= threshold) {
+            Some(ipeak) => (ipeak + 1..n_samples)
+                .find(|&i| intensities[i] < threshold)
+                .map(|i| {
+                    let frac =
+                        (intensities[i - 1] - threshold) / (intensities[i - 1] - intensities[i]);
+                    angles[i - 1] + frac * (angles[i] - angles[i - 1])
+                })
+                .unwrap_or_else(|| *angles.last().unwrap()),
+            None => 0.0,
+        }
+    }
+
     /// This resolves a plane index into a plane. 
     /// If the index is between 0 and the number of planes - 1, this function will
     /// just directly resolve the index. However, if the index is outside of this range
@@ -85,55 +819,1042 @@ impl PhotometricWeb {
     /// the index, so going lower than zero and higher than nplanes - 1 is permitted. 
     pub fn get_adjacent_planes(&self, iplane: i32) -> (&Plane, &Plane) {        
         // Get the plane lower, remembering to resolve if this if the first plane. 
-        let lplane = self.resolve_index(iplane as i32 - 1);
-        let uplane = self.resolve_index(iplane as i32 + 1);
+        let lplane = self.resolve_index(iplane - 1);
+        let uplane = self.resolve_index(iplane + 1);
         (lplane, uplane)
     }
 
-    /// Returns a specified pair of C-Planes, arranged appropriately for plottuing. 
-    /// For example, the most common usage would be the C0-C180 and C90-C270 pairs.
-    /// If one of both of the planes are not defined within the photometric web, None is returned.
-    /// If the process has been successful, a Some with a tuple containing (angles, intensities) Vecs is returned. 
-    pub fn get_cplane_pair(&self, angle_lower_deg: f64, angle_upper_deg: f64) -> Option<(Vec<f64>, Vec<f64>)> {
-        let (angle_lower, angle_upper) = if angle_upper_deg > angle_lower_deg { (angle_lower_deg, angle_upper_deg) } else { (angle_upper_deg, angle_lower_deg) };
-        let pl1 = self.planes().iter().find(|pl| pl.angle_deg() == angle_lower);
-        let pl2 = self.planes().iter().find(|pl| pl.angle_deg() == angle_upper);
-
-        // In this case one or both of the planes does not exist, so return None. 
-        if pl1.is_none() || pl2.is_none() { return None };
-
-        let angles: Vec<f64> = pl2.unwrap().angles()
-            .into_iter()
-            .rev()
-            .map(|ang| (2.0 * PI) - ang) // Mirror around the 0 angle points. 
-            .chain(pl1.unwrap().angles().iter().map(|val| *val))
-            .collect();
+    /// Returns the plane nearest to the given C-angle (in radians), accounting for wrapping around 2 pi.
+    /// If the web has no planes, `None` is returned.
+    pub fn plane_at_angle(&self, c_angle_rad: f64) -> Option<&Plane> {
+        self.planes.iter().min_by(|pl1, pl2| {
+            Self::angular_distance(pl1.angle(), c_angle_rad)
+                .total_cmp(&Self::angular_distance(pl2.angle(), c_angle_rad))
+        })
+    }
+
+    /// Returns the two planes either side of the given C-angle (in radians), as (lower, upper).
+    /// The planes are assumed to be sorted by angle, and the search wraps around 2 pi, so an angle
+    /// beyond the last plane is bracketed by the last and first planes. If the angle falls exactly
+    /// on a plane, that plane is returned as the lower plane. If the web has no planes, `None` is returned.
+    pub fn bracketing_planes(&self, c_angle_rad: f64) -> Option<(&Plane, &Plane)> {
+        if self.planes.is_empty() {
+            return None;
+        }
+
+        let angle = c_angle_rad.rem_euclid(2.0 * PI);
+        let ilower = match self.planes.iter().rposition(|pl| pl.angle() <= angle) {
+            Some(ilower) => ilower as i32,
+            // The angle is before the first plane, so wrap around to the last.
+            None => -1,
+        };
+        Some((self.resolve_index(ilower), self.resolve_index(ilower + 1)))
+    }
+
+    /// Returns the (gamma angles, intensities) of the plane nearest the given C-angle (in degrees) and the plane
+    /// opposite it, ready for a polar plot of the full slice through the luminaire. The gamma angles are in degrees,
+    /// with the opposite plane given at negative angles, so a slice measured from 0 -> 180 degrees runs from -180 -> 180.
+    /// Both arrays are empty if the web has no planes.
+    pub fn polar_plot_data(&self, c_angle_deg: f64) -> (Vec<f64>, Vec<f64>) {
+        match self.get_cplane_pair(c_angle_deg, c_angle_deg + 180.0) {
+            Some((angles, intensities)) => (
+                angles.into_iter().map(radians_to_degrees).collect(),
+                intensities,
+            ),
+            None => (Vec::new(), Vec::new()),
+        }
+    }
+
+    /// Returns a specified pair of C-Planes, arranged appropriately for plotting.
+    /// For example, the most common usage would be the C0-C180 and C90-C270 pairs.
+    /// The planes nearest to each of the requested C-angles (in degrees) are used, so None is only returned
+    /// if the web has no planes. Otherwise, a Some with a tuple containing (angles, intensities) Vecs is returned.
+    /// The angles are in radians, ordered from the most negative to the most positive: the plane nearest the
+    /// upper of the two C-angles is given at negative gamma angles, followed by the plane nearest the lower
+    /// C-angle at positive gamma angles. Each plane keeps its own sample at nadir, so zero appears twice.
+    pub fn get_cplane_pair(
+        &self,
+        angle_lower_deg: f64,
+        angle_upper_deg: f64,
+    ) -> Option<(Vec<f64>, Vec<f64>)> {
+        let (angle_lower, angle_upper) = if angle_upper_deg > angle_lower_deg {
+            (angle_lower_deg, angle_upper_deg)
+        } else {
+            (angle_upper_deg, angle_lower_deg)
+        };
+        let pl1 = self.plane_at_angle(degrees_to_radians(angle_lower))?;
+        let pl2 = self.plane_at_angle(degrees_to_radians(angle_upper))?;
+
+        let angles: Vec<f64> = pl2
+            .angles()
+            .iter()
+            .rev()
+            .map(|ang| -ang) // Mirror around the 0 angle points.
+            .chain(pl1.angles().iter().copied())
+            .collect();
+
+        let intensities: Vec<f64> = pl2
+            .intensities()
+            .iter()
+            .rev()
+            .chain(pl1.intensities())
+            .copied()
+            .collect();
+
+        Some((angles, intensities))
+    }
+
+    /// Determines the tightest symmetry that the photometric web exhibits.
+    /// Planes are compared against their mirrored counterparts (using the same conventions as the `mirror_*`
+    /// functions) and are considered equal if all intensities agree to within `tolerance`.
+    /// The symmetries are checked from the tightest (about the vertical axis) to the loosest, returning
+    /// `EulumdatSymmetry::NoSymmetry` if none apply.
+    pub fn detect_symmetry(&self, tolerance: f64) -> EulumdatSymmetry {
+        if self.planes.is_empty() {
+            return EulumdatSymmetry::NoSymmetry;
+        }
+
+        [
+            EulumdatSymmetry::AboutVerticalAxis,
+            EulumdatSymmetry::C0C180C90C270Plane,
+            EulumdatSymmetry::C0C180Plane,
+            EulumdatSymmetry::C90C270Plane,
+        ]
+        .into_iter()
+        .find(|sym| self.is_symmetric(sym, tolerance))
+        .unwrap_or(EulumdatSymmetry::NoSymmetry)
+    }
+
+    /// Checks whether the photometric web satisfies the given symmetry, comparing intensities to within `tolerance`.
+    /// Every web trivially satisfies `EulumdatSymmetry::NoSymmetry`.
+    pub fn is_symmetric(&self, symmetry: &EulumdatSymmetry, tolerance: f64) -> bool {
+        // C0-C180 symmetry mirrors C to 2 pi - C, and C90-C270 symmetry mirrors C to pi - C.
+        let c0c180 = |web: &Self| web.is_mirror_symmetric(|angle| 2.0 * PI - angle, tolerance);
+        let c90c270 = |web: &Self| web.is_mirror_symmetric(|angle| PI - angle, tolerance);
+        match symmetry {
+            EulumdatSymmetry::NoSymmetry => true,
+            // Every plane identical to the first means that the distribution is symmetric around the vertical axis.
+            EulumdatSymmetry::AboutVerticalAxis => match self.planes.first() {
+                Some(first) => self
+                    .planes
+                    .iter()
+                    .all(|pl| Self::intensities_match(first, pl, tolerance)),
+                None => false,
+            },
+            EulumdatSymmetry::C0C180Plane => c0c180(self),
+            EulumdatSymmetry::C90C270Plane => c90c270(self),
+            EulumdatSymmetry::C0C180C90C270Plane => c0c180(self) && c90c270(self),
+        }
+    }
+
+    /// Enforces the given azimuthal symmetry on the web by replacing the intensities of each plane with the average
+    /// of the plane and its mirrored counterparts, using the same conventions as `is_symmetric`. Mirrored planes
+    /// that are missing from the web, or that have a different number of samples, are ignored.
+    /// `EulumdatSymmetry::NoSymmetry` leaves the web unchanged.
+    pub fn symmetrize(&mut self, symmetry: EulumdatSymmetry) {
+        let mirrors = |angle: f64| -> Vec<f64> {
+            match symmetry {
+                EulumdatSymmetry::NoSymmetry | EulumdatSymmetry::AboutVerticalAxis => vec![angle],
+                EulumdatSymmetry::C0C180Plane => vec![angle, 2.0 * PI - angle],
+                EulumdatSymmetry::C90C270Plane => vec![angle, PI - angle],
+                EulumdatSymmetry::C0C180C90C270Plane => {
+                    vec![angle, 2.0 * PI - angle, PI - angle, PI + angle]
+                }
+            }
+        };
+        if symmetry == EulumdatSymmetry::NoSymmetry {
+            return;
+        }
+
+        // Always average the planes in web order, so that every plane in a group gets identical intensities.
+        let original = self.planes.clone();
+        for pl in self.planes.iter_mut() {
+            let targets = mirrors(pl.angle());
+            let group: Vec<&Plane> = original
+                .iter()
+                .filter(|other| other.intensities().len() == pl.intensities().len())
+                .filter(|other| {
+                    symmetry == EulumdatSymmetry::AboutVerticalAxis
+                        || targets
+                            .iter()
+                            .any(|target| Self::angles_match(other.angle(), *target))
+                })
+                .collect();
+            if group.is_empty() {
+                continue;
+            }
+            let intensities = (0..pl.intensities().len())
+                .map(|i| {
+                    group
+                        .iter()
+                        .map(|other| other.intensities()[i])
+                        .sum::<f64>()
+                        / group.len() as f64
+                })
+                .collect::<Vec<f64>>();
+            pl.set_intensities(intensities);
+        }
+    }
+
+    /// Counts the planes that are distinct from one another, regardless of their C-angle.
+    /// Two planes are considered duplicates if their gamma angles and intensities all agree to within `tolerance`,
+    /// such as the copies introduced when mirroring a symmetric distribution.
+    pub fn unique_plane_count(&self, tolerance: f64) -> usize {
+        let mut unique: Vec<&Plane> = Vec::new();
+        for pl in self.planes.iter() {
+            let is_duplicate = unique.iter().any(|other| {
+                Self::intensities_match(pl, other, tolerance)
+                    && pl.angles().len() == other.angles().len()
+                    && pl
+                        .angles()
+                        .iter()
+                        .zip(other.angles())
+                        .all(|(a, b)| (a - b).abs() <= tolerance)
+            });
+            if !is_duplicate {
+                unique.push(pl);
+            }
+        }
+        unique.len()
+    }
+
+    /// Reduces the photometric web down to the representative planes for the given symmetry, as would be stored in a file.
+    /// This is the inverse of the `mirror_*` functions, keeping the planes in the following ranges:
+    /// - `AboutVerticalAxis`: only the first plane.
+    /// - `C0C180Plane`: 0 -> 180 degrees.
+    /// - `C90C270Plane`: 90 -> 270 degrees.
+    /// - `C0C180C90C270Plane`: 0 -> 90 degrees.
+    /// - `NoSymmetry`: all planes.
+    ///
+    /// If the web does not satisfy the symmetry (to within `SYMMETRY_TOLERANCE`), an error is returned.
+    /// Note that the plane widths of the collapsed web only describe the stored planes, so the web should be
+    /// expanded again before integrating.
+    pub fn collapse_symmetry(&self, symmetry: EulumdatSymmetry) -> Result<PhotometricWeb, Error> {
+        if self.planes.is_empty() {
+            return Err(Error::NoPlanes);
+        }
+        if !self.is_symmetric(&symmetry, SYMMETRY_TOLERANCE) {
+            return Err(Error::SymmetryNotSatisfied);
+        }
+
+        let in_range = |pl: &&Plane, lower: f64, upper: f64| {
+            pl.angle() >= lower - PLANE_ANGLE_TOLERANCE
+                && pl.angle() <= upper + PLANE_ANGLE_TOLERANCE
+        };
+        let planes: Vec<Plane> = match symmetry {
+            EulumdatSymmetry::NoSymmetry => self.planes.clone(),
+            EulumdatSymmetry::AboutVerticalAxis => vec![self.planes[0].clone()],
+            EulumdatSymmetry::C0C180Plane => self
+                .planes
+                .iter()
+                .filter(|pl| in_range(pl, 0.0, PI))
+                .cloned()
+                .collect(),
+            EulumdatSymmetry::C90C270Plane => self
+                .planes
+                .iter()
+                .filter(|pl| in_range(pl, FRAC_PI_2, 3.0 * FRAC_PI_2))
+                .cloned()
+                .collect(),
+            EulumdatSymmetry::C0C180C90C270Plane => self
+                .planes
+                .iter()
+                .filter(|pl| in_range(pl, 0.0, FRAC_PI_2))
+                .cloned()
+                .collect(),
+        };
+
+        let mut web = PhotometricWeb::new();
+        web.set_planes(planes);
+        web.copy_metadata_from(self);
+        Ok(web)
+    }
+
+    /// Checks that the photometric web is internally consistent, returning an error describing the first problem found.
+    /// - Each plane must have the same number of angles as intensities.
+    /// - Every plane must have the same number of gamma samples.
+    /// - The plane angles must be strictly increasing, allowing for a single wrap around 2 pi.
+    pub fn validate(&self) -> Result<(), Error> {
+        let n_samples = match self.planes.first() {
+            Some(pl) => pl.n_samples(),
+            None => return Ok(()),
+        };
+
+        for (iplane, pl) in self.planes.iter().enumerate() {
+            if pl.intensities().len() != pl.n_samples() {
+                return Err(Error::InconsistentIntensitiesInPlane(
+                    pl.n_samples(),
+                    pl.intensities().len(),
+                ));
+            }
+            if pl.n_samples() != n_samples {
+                return Err(Error::InconsistentSamplesInPlanes(
+                    n_samples,
+                    pl.n_samples(),
+                    iplane,
+                ));
+            }
+        }
+
+        // Step through the planes, accumulating the angle swept. If the planes are increasing
+        // this should never go backwards or complete a full revolution.
+        let mut swept = 0.0;
+        for (iplane, pair) in self.planes.windows(2).enumerate() {
+            let step = (pair[1].angle() - pair[0].angle()).rem_euclid(2.0 * PI);
+            swept += step;
+            if step <= PLANE_ANGLE_TOLERANCE || swept >= 2.0 * PI - PLANE_ANGLE_TOLERANCE {
+                return Err(Error::PlaneAnglesNotIncreasing(iplane + 1));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Resamples the web onto `n_steps` evenly spaced C-planes, starting at C0, interpolating between the existing
+    /// planes as in `intensity_at`. A spherically symmetric web is revolved around the vertical axis, copying its
+    /// single plane to each step, so fewer steps trade smoothness for size (see `DEFAULT_AZIMUTH_STEPS`).
+    /// Every plane is sampled at the gamma angles of the first plane. An empty web, or zero steps, gives an empty web.
+    ///
+    /// Note that integrating a spherically symmetric web does not depend on a number of steps, as its single
+    /// plane already has a width of `PlaneWidth::Symmetric(2 pi)`.
+    pub fn resample_azimuth(&self, n_steps: usize) -> PhotometricWeb {
+        let mut web = PhotometricWeb::new();
+        web.copy_metadata_from(self);
+        let first = match self.planes.first() {
+            Some(first) if n_steps > 0 => first,
+            _ => return web,
+        };
+
+        let planes = (0..n_steps)
+            .map(|istep| {
+                let c_angle = 2.0 * PI * istep as f64 / n_steps as f64;
+                let mut plane = first.clone();
+                plane.set_angle(c_angle);
+                plane.set_intensities(
+                    first
+                        .angles()
+                        .iter()
+                        .map(|gamma| self.intensity_at(c_angle, *gamma))
+                        .collect::<Vec<f64>>(),
+                );
+                plane
+            })
+            .collect();
+        web.set_planes(planes);
+        web
+    }
+
+    /// Returns a smaller copy of the web with `target_planes` equally spaced planes (as in `resample_azimuth`), each
+    /// resampled to `target_gamma` gamma angles (as in `Plane::resample`). Coarser sampling changes the integrated
+    /// intensity, so the intensities are then rescaled so that `total_intensity` matches that of the original web.
+    /// If either web integrates to zero, no rescaling is applied.
+    pub fn downsample(&self, target_gamma: usize, target_planes: usize) -> PhotometricWeb {
+        let mut web = self.resample_azimuth(target_planes);
+        let planes = web
+            .planes
+            .iter()
+            .map(|pl| pl.resample(target_gamma))
+            .collect();
+        web.set_planes(planes);
+
+        let (original, downsampled) = (self.total_intensity(), web.total_intensity());
+        if original != 0.0 && downsampled != 0.0 {
+            let factor = original / downsampled;
+            for pl in web.planes.iter_mut() {
+                let intensities = pl
+                    .intensities()
+                    .iter()
+                    .map(|intens| intens * factor)
+                    .collect::<Vec<f64>>();
+                pl.set_intensities(intensities);
+            }
+        }
+        web
+    }
+
+    /// Merges the planes of two partial webs into a single web, such as when the front and back halves of a
+    /// luminaire are provided in separate files. The planes are sorted by angle, and the plane widths are
+    /// recomputed for the merged web. Both webs must share the same gamma angles, and an error is returned
+    /// if they do not, or if both webs contain a plane at the same C-angle.
+    pub fn merge(&self, other: &PhotometricWeb) -> Result<PhotometricWeb, Error> {
+        let mut planes: Vec<Plane> = self
+            .planes
+            .iter()
+            .chain(other.planes.iter())
+            .cloned()
+            .collect();
+        if planes.is_empty() {
+            return Err(Error::NoPlanes);
+        }
+
+        // Every plane must be sampled at the same gamma angles.
+        let gamma_angles = planes[0].angles().to_vec();
+        let same_gamma = |pl: &Plane| {
+            pl.n_samples() == gamma_angles.len()
+                && pl
+                    .angles()
+                    .iter()
+                    .zip(gamma_angles.iter())
+                    .all(|(a, b)| (a - b).abs() <= PLANE_ANGLE_TOLERANCE)
+        };
+        if !planes.iter().all(same_gamma) {
+            return Err(Error::InconsistentPlaneAngles);
+        }
+
+        // Sort by angle, and check that no two planes share an azimuth (including across 0 / 2 pi).
+        planes.sort_by(|pl1, pl2| pl1.angle().total_cmp(&pl2.angle()));
+        let n_planes = planes.len();
+        if let Some(iplane) = (0..n_planes).find(|&i| {
+            n_planes > 1
+                && Self::angles_match(planes[i].angle(), planes[(i + 1) % n_planes].angle())
+        }) {
+            return Err(Error::DuplicatePlaneAngle(planes[iplane].angle_deg()));
+        }
+
+        let mut web = PhotometricWeb::new();
+        web.set_planes(planes);
+        web.copy_metadata_from(self);
+        web.copy_metadata_from(other);
+        Ok(web)
+    }
+
+    /// Copies the metadata of another web (input power, rated lumens and luminous area) that this web is missing,
+    /// such as when deriving a new web from an existing one. Metadata that is already set is kept, so copying
+    /// from several webs in turn gives precedence to the first.
+    pub(crate) fn copy_metadata_from(&mut self, other: &PhotometricWeb) {
+        self.input_watts = self.input_watts.or(other.input_watts);
+        self.total_rated_lumens = self.total_rated_lumens.or(other.total_rated_lumens);
+        if self.luminous_opening.is_none() {
+            self.luminous_opening = other.luminous_opening.clone();
+        }
+        self.luminous_area_heights = self.luminous_area_heights.or(other.luminous_area_heights);
+    }
+
+    /// Checks that every plane has a counterpart at the mirrored angle with matching intensities.
+    fn is_mirror_symmetric<F: Fn(f64) -> f64>(&self, mirror: F, tolerance: f64) -> bool {
+        self.planes.iter().all(|pl| {
+            let mirror_angle = mirror(pl.angle());
+            match self
+                .planes
+                .iter()
+                .find(|other| Self::angles_match(other.angle(), mirror_angle))
+            {
+                Some(other) => Self::intensities_match(pl, other, tolerance),
+                None => false,
+            }
+        })
+    }
+
+    /// Checks whether two plane angles point in the same direction, wrapping around 2 pi.
+    fn angles_match(angle1: f64, angle2: f64) -> bool {
+        Self::angular_distance(angle1, angle2) <= PLANE_ANGLE_TOLERANCE
+    }
+
+    /// The smallest angle (in radians) between two C-angles, accounting for wrapping around 2 pi.
+    fn angular_distance(angle1: f64, angle2: f64) -> f64 {
+        let diff = (angle1 - angle2).rem_euclid(2.0 * PI);
+        diff.min(2.0 * PI - diff)
+    }
+
+    /// Checks whether two planes have the same intensities to within the given tolerance.
+    fn intensities_match(plane1: &Plane, plane2: &Plane, tolerance: f64) -> bool {
+        plane1.intensities().len() == plane2.intensities().len()
+            && plane1
+                .intensities()
+                .iter()
+                .zip(plane2.intensities())
+                .all(|(a, b)| (a - b).abs() <= tolerance)
+    }
+}
+
+impl TryFrom<&Path> for PhotometricWeb {
+    type Error = crate::err::Error;
+
+    fn try_from(path: &Path) -> Result<Self, Self::Error> {
+        Self::from_path(path)
+    }
+}
+
+impl std::fmt::Display for PhotometricWeb {
+    /// Writes a concise, single line summary of the web, for logging. Use `Debug` for the full contents.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.planes.is_empty() {
+            return write!(f, "PhotometricWeb: 0 planes, no samples");
+        }
+
+        let (gamma_min, gamma_max) = self
+            .planes
+            .iter()
+            .flat_map(|pl| pl.angles_deg())
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), gamma| {
+                (min.min(gamma), max.max(gamma))
+            });
+        write!(f, "PhotometricWeb: {} planes, ", self.n_planes())?;
+        if gamma_min <= gamma_max {
+            write!(f, "gamma {}-{} deg, ", gamma_min, gamma_max)?;
+        } else {
+            write!(f, "no samples, ")?;
+        }
+        let symmetric = if self.is_spherically_symmetric() {
+            "yes"
+        } else {
+            "no"
+        };
+        // Integrating relative intensities gives the flux per 1000 lm of lamp flux, rather than lumens.
+        let has_units = |units: IntensityUnits| self.planes.iter().all(|pl| *pl.units() == units);
+        if has_units(IntensityUnits::Candela) {
+            write!(f, "total flux {:.2} lm, ", self.total_flux())?;
+        } else if has_units(IntensityUnits::CandelaPerKilolumen) {
+            write!(f, "total flux {:.2} lm/klm, ", self.total_flux())?;
+        } else {
+            write!(f, "total flux unknown (mixed units), ")?;
+        }
+        write!(f, "spherically symmetric: {}", symmetric)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        io::{eulumdat::EulumdatSymmetry, ies::lum_opening::IesLuminousOpening},
+        util::geom::degrees_to_radians,
+    };
+
+    use super::{
+        CutoffClass, IntegrationMethod, IntensityUnits, PhotometricWeb, PhotometricWebBuilder,
+        Plane, DEFAULT_AZIMUTH_STEPS, ZONAL_MULTIPLIER_CONSTANTS,
+    };
+    use crate::photweb::{mirror_first_hemisphere, mirror_first_quadrant};
+    use approx::assert_abs_diff_eq;
+    use std::{
+        f64::consts::{FRAC_PI_2, PI},
+        path::Path,
+    };
+
+    /// Builds a web of planes every 10 degrees, where the intensities in each plane are scaled
+    /// by the provided function of the C-angle (in radians).
+    fn web_from_cangle_fn<F: Fn(f64) -> f64>(func: F) -> PhotometricWeb {
+        let mut web = PhotometricWeb::new();
+        web.set_planes(
+            (0..360)
+                .step_by(10)
+                .map(|ang_deg| {
+                    let mut plane = Plane::new();
+                    plane.set_angle_degrees(ang_deg as f64);
+                    plane.set_angles_degrees(
+                        &(0..181)
+                            .step_by(10)
+                            .map(|ang| ang as f64)
+                            .collect::<Vec<f64>>(),
+                    );
+                    let scale = func(plane.angle());
+                    plane.set_intensities(
+                        plane
+                            .angles()
+                            .iter()
+                            .map(|gamma| scale * (1.0 + gamma.cos()))
+                            .collect::<Vec<f64>>(),
+                    );
+                    plane
+                })
+                .collect::<Vec<Plane>>(),
+        );
+        web
+    }
+
+    /// Check that by default, our integrated intensity is zero.
+    #[test]
+    fn test_integrate_zero() {
+        let web = PhotometricWeb::new();
+        let res = web.total_intensity();
+        assert_eq!(res, 0.0);
+    }
+
+    /// A web measured over the lower hemisphere (0 -> 90 degrees in gamma, 0 -> 360 degrees in C)
+    /// covers half of the sphere, so should span 2 pi steradians.
+    #[test]
+    fn test_measured_solid_angle() {
+        let mut plane = Plane::new();
+        plane.set_angles_degrees(&(0..91).map(|ang_i| ang_i as f64).collect::<Vec<f64>>());
+        plane.set_intensities(plane.angles().iter().map(|_| 1.0).collect::<Vec<f64>>());
+
+        let mut web = PhotometricWeb::new();
+        web.set_planes(
+            (0..360)
+                .step_by(10)
+                .map(|ang_deg| {
+                    let mut new_plane = plane.clone();
+                    new_plane.set_angle_degrees(ang_deg as f64);
+                    new_plane
+                })
+                .collect::<Vec<Plane>>(),
+        );
+        assert_abs_diff_eq!(web.measured_solid_angle(), 2.0 * PI, epsilon = 1E-9);
+
+        // The full sphere covers 4 pi steradians.
+        let web = web_from_cangle_fn(|_| 1.0);
+        assert_abs_diff_eq!(web.measured_solid_angle(), 4.0 * PI, epsilon = 1E-9);
+    }
+
+    /// Check that iterating over the samples visits every sample in every plane.
+    #[test]
+    fn test_iter_samples() {
+        let web = web_from_cangle_fn(|c| 1.0 + c.cos());
+        let n_samples: usize = web.planes().iter().map(|pl| pl.n_samples()).sum();
+        assert_eq!(web.iter_samples().count(), n_samples);
+
+        let (c_angle, gamma, intensity) = web.iter_samples().nth(20).unwrap();
+        let plane = &web.planes()[1];
+        assert_eq!(c_angle, plane.angle());
+        assert_eq!(gamma, plane.angles()[20 - web.planes()[0].n_samples()]);
+        assert_eq!(
+            intensity,
+            plane.intensities()[20 - web.planes()[0].n_samples()]
+        );
+    }
+
+    /// Check that looking up planes by angle finds the nearest and bracketing planes,
+    /// including for angles that wrap around the 0 / 2 pi point.
+    #[test]
+    fn test_plane_at_angle() {
+        let web = web_from_cangle_fn(|_| 1.0);
+
+        let plane = web.plane_at_angle(degrees_to_radians(24.0)).unwrap();
+        assert_abs_diff_eq!(plane.angle_deg(), 20.0, epsilon = 1E-6);
+        let plane = web.plane_at_angle(degrees_to_radians(357.0)).unwrap();
+        assert_abs_diff_eq!(plane.angle_deg(), 0.0, epsilon = 1E-6);
+
+        let (lower, upper) = web.bracketing_planes(degrees_to_radians(24.0)).unwrap();
+        assert_abs_diff_eq!(lower.angle_deg(), 20.0, epsilon = 1E-6);
+        assert_abs_diff_eq!(upper.angle_deg(), 30.0, epsilon = 1E-6);
+
+        let (lower, upper) = web.bracketing_planes(degrees_to_radians(355.0)).unwrap();
+        assert_abs_diff_eq!(lower.angle_deg(), 350.0, epsilon = 1E-6);
+        assert_abs_diff_eq!(upper.angle_deg(), 0.0, epsilon = 1E-6);
+
+        let (lower, upper) = web.bracketing_planes(degrees_to_radians(-5.0)).unwrap();
+        assert_abs_diff_eq!(lower.angle_deg(), 350.0, epsilon = 1E-6);
+        assert_abs_diff_eq!(upper.angle_deg(), 0.0, epsilon = 1E-6);
+
+        // An empty web has no planes to find.
+        let web = PhotometricWeb::new();
+        assert!(web.plane_at_angle(0.0).is_none());
+        assert!(web.bracketing_planes(0.0).is_none());
+    }
+
+    /// Check that a web can be read straight from a file path.
+    #[test]
+    fn test_from_path() {
+        let path = Path::new("./src/io/eulumdat/example.ldt");
+        match PhotometricWeb::from_path(path) {
+            Ok(web) => assert_eq!(web.n_planes(), 20),
+            Err(e) => panic!("Read error: {}", e),
+        }
+
+        match PhotometricWeb::try_from(path) {
+            Ok(web) => assert_eq!(web.n_planes(), 20),
+            Err(e) => panic!("Read error: {}", e),
+        }
+
+        assert!(PhotometricWeb::from_path(Path::new("./src/io/eulumdat/missing.ldt")).is_err());
+    }
+
+    /// Check that a web written in each format can be read back by the builder.
+    #[test]
+    fn test_write_file() {
+        let web = PhotometricWeb::from_path(Path::new("./src/io/eulumdat/example.ldt")).unwrap();
+        let dir = std::env::temp_dir().join("lidrs_test_write_file");
+        std::fs::create_dir_all(&dir).unwrap();
+
+        for filename in ["written.ies", "written.ldt"] {
+            let path = dir.join(filename);
+            if let Err(e) = web.write_file(&path) {
+                panic!("Write error: {}", e);
+            }
+            match PhotometricWebBuilder::from_file(&path).build() {
+                Ok(written) => {
+                    assert!(written.approx_eq(&web, 1E-9));
+                    assert_eq!(written.total_rated_lumens(), web.total_rated_lumens());
+                    assert_eq!(written.input_watts(), web.input_watts());
+                }
+                Err(e) => panic!("Read error for {}: {}", filename, e),
+            }
+        }
+
+        assert!(matches!(
+            web.write_file(&dir.join("written.txt")),
+            Err(crate::err::Error::InvalidFileType(_))
+        ));
+    }
+
+    #[test]
+    fn test_total_intensity_with() {
+        // A constant distribution of 1 cd over the sphere gives 4 pi by either method.
+        let web = web_from_cangle_fn(|_| 0.5);
+        let mut constant = PhotometricWeb::new();
+        constant.set_planes(
+            web.planes()
+                .iter()
+                .map(|pl| {
+                    let mut plane = pl.clone();
+                    plane.set_intensities(vec![1.0; pl.n_samples()]);
+                    plane
+                })
+                .collect(),
+        );
+        assert_eq!(
+            constant.total_intensity_with(IntegrationMethod::Midpoint),
+            constant.total_intensity()
+        );
+        assert_abs_diff_eq!(
+            constant.total_intensity_with(IntegrationMethod::Trapezoidal),
+            4.0 * PI,
+            epsilon = 0.05
+        );
+    }
+
+    #[test]
+    fn test_min_intensity_and_dynamic_range() {
+        // The intensity is zero at gamma = 180 degrees, so the smallest non-zero intensity is at 170 degrees in C0.
+        let web = web_from_cangle_fn(|c| 1.0 + c);
+        let (c_angle, gamma, min_intensity) = web.min_intensity();
+        assert_eq!(c_angle, 0.0);
+        assert_abs_diff_eq!(gamma, degrees_to_radians(170.0), epsilon = 1E-12);
+        let expected_min = 1.0 + degrees_to_radians(170.0).cos();
+        assert_abs_diff_eq!(min_intensity, expected_min, epsilon = 1E-12);
+
+        // The peak is at nadir in the C350 plane.
+        let expected_max = 2.0 * (1.0 + degrees_to_radians(350.0));
+        assert_abs_diff_eq!(
+            web.dynamic_range(),
+            expected_max / expected_min,
+            epsilon = 1E-9
+        );
+
+        let dark = web_from_cangle_fn(|_| 0.0);
+        assert_eq!(dark.min_intensity(), (0.0, 0.0, 0.0));
+        assert!(dark.dynamic_range().is_nan());
+    }
+
+    #[test]
+    fn test_polar_plot_data() {
+        // For a distribution that is the same in every plane, the slice is symmetric about nadir.
+        let web = web_from_cangle_fn(|_| 1.0);
+        let (angles, intensities) = web.polar_plot_data(90.0);
+        assert_eq!(angles.len(), 38);
+        assert_abs_diff_eq!(angles[0], -180.0, epsilon = 1E-9);
+        assert_abs_diff_eq!(angles[37], 180.0, epsilon = 1E-9);
+        for i in 0..angles.len() {
+            assert_abs_diff_eq!(angles[i], -angles[37 - i], epsilon = 1E-9);
+            assert_abs_diff_eq!(intensities[i], intensities[37 - i], epsilon = 1E-12);
+        }
+
+        // The requested plane is given at positive angles, and the opposite plane at negative angles.
+        let web = web_from_cangle_fn(|c| 1.0 + c);
+        let (_, intensities) = web.polar_plot_data(270.0);
+        assert_abs_diff_eq!(intensities[18], 2.0 * (1.0 + PI / 2.0), epsilon = 1E-9);
+        assert_abs_diff_eq!(
+            intensities[19],
+            2.0 * (1.0 + 3.0 * PI / 2.0),
+            epsilon = 1E-9
+        );
+
+        assert_eq!(
+            PhotometricWeb::new().polar_plot_data(0.0),
+            (Vec::new(), Vec::new())
+        );
+    }
+
+    #[test]
+    fn test_get_cplane_pair() {
+        let web = web_from_cangle_fn(|c| 1.0 + c);
+        match web.get_cplane_pair(0.0, 180.0) {
+            Some((angles, intensities)) => {
+                assert_eq!(angles.len(), 38);
+                assert_eq!(intensities.len(), 38);
+                // The C180 plane runs from -180 degrees to nadir, followed by the C0 plane from nadir to 180 degrees.
+                assert_abs_diff_eq!(angles[0], -PI, epsilon = 1E-12);
+                assert_eq!(angles[18], 0.0);
+                assert_eq!(angles[19], 0.0);
+                assert_abs_diff_eq!(angles[37], PI, epsilon = 1E-12);
+                assert!(angles.windows(2).all(|pair| pair[1] >= pair[0]));
+                assert_abs_diff_eq!(intensities[18], 2.0 * (1.0 + PI), epsilon = 1E-12);
+                assert_abs_diff_eq!(intensities[19], 2.0, epsilon = 1E-12);
+            }
+            None => panic!("No C-plane pair found."),
+        }
+
+        // The order of the requested angles doesn't matter, and the nearest planes are used.
+        assert_eq!(
+            web.get_cplane_pair(181.0, 1.0),
+            web.get_cplane_pair(0.0, 180.0)
+        );
+        assert!(PhotometricWeb::new().get_cplane_pair(0.0, 180.0).is_none());
+    }
+
+    #[test]
+    fn test_flip_gamma() {
+        let mut web = web_from_cangle_fn(|_| 1.0);
+        let peak_gamma = |web: &PhotometricWeb| {
+            web.iter_samples()
+                .max_by(|(_, _, intens1), (_, _, intens2)| intens1.total_cmp(intens2))
+                .map(|(_, gamma, _)| gamma)
+                .unwrap()
+        };
+        assert_eq!(peak_gamma(&web), 0.0);
+        let flux = web.total_flux();
+
+        web.flip_gamma();
+        assert_abs_diff_eq!(peak_gamma(&web), PI, epsilon = 1E-12);
+        assert!(web
+            .planes()
+            .iter()
+            .all(|pl| pl.angles().windows(2).all(|pair| pair[1] > pair[0])));
+        assert_abs_diff_eq!(web.total_flux(), flux, epsilon = 1E-9);
+        assert!(web.validate().is_ok());
+    }
+
+    #[test]
+    fn test_mirror_azimuth() {
+        let original = web_from_cangle_fn(|c| 1.0 + c);
+        let mut web = web_from_cangle_fn(|c| 1.0 + c);
+        web.mirror_azimuth(0.0);
+        assert_eq!(web.n_planes(), original.n_planes());
+
+        // The plane formerly at 30 degrees is now at 330 degrees, and the C0 plane is unchanged.
+        let original_plane = original.plane_at_angle(degrees_to_radians(30.0)).unwrap();
+        let mirrored_plane = web.plane_at_angle(degrees_to_radians(330.0)).unwrap();
+        assert_abs_diff_eq!(mirrored_plane.angle_deg(), 330.0, epsilon = 1E-9);
+        assert_eq!(mirrored_plane.intensities(), original_plane.intensities());
+        assert_eq!(web.planes()[0].angle(), 0.0);
+        assert_eq!(
+            web.planes()[0].intensities(),
+            original.planes()[0].intensities()
+        );
+        assert!(web.validate().is_ok());
+        assert_abs_diff_eq!(web.total_flux(), original.total_flux(), epsilon = 1E-9);
+
+        // Mirroring twice about any axis restores the original web.
+        web.mirror_azimuth(0.0);
+        assert!(web.approx_eq(&original, 1E-9));
+        web.mirror_azimuth(degrees_to_radians(45.0));
+        web.mirror_azimuth(degrees_to_radians(45.0));
+        assert!(web.approx_eq(&original, 1E-9));
+    }
+
+    #[test]
+    fn test_total_flux_lumens() {
+        let mut web = web_from_cangle_fn(|_| 1.0);
+        match web.total_flux_lumens() {
+            Ok(lumens) => assert_eq!(lumens, web.total_flux()),
+            Err(e) => panic!("Units error: {}", e),
+        }
+
+        web.mut_planes()[2].set_units(IntensityUnits::CandelaPerKilolumen);
+        assert!(matches!(
+            web.total_flux_lumens(),
+            Err(crate::ops::err::Error::IncompatibleUnits(2))
+        ));
+    }
+
+    #[test]
+    fn test_try_from_planes() {
+        let web = web_from_cangle_fn(|_| 1.0);
+        match PhotometricWeb::try_from_planes(web.planes().to_vec()) {
+            Ok(checked) => assert!(checked.approx_eq(&web, 1E-12)),
+            Err(e) => panic!("Validation error: {}", e),
+        }
+
+        // A ragged web, with one plane sampled at fewer gamma angles than the others.
+        let mut planes = web.planes().to_vec();
+        planes[3].set_angles_degrees(&[0.0, 90.0, 180.0]);
+        planes[3].set_intensities(vec![1.0; 3]);
+        assert!(matches!(
+            PhotometricWeb::try_from_planes(planes),
+            Err(crate::ops::err::Error::InconsistentSamplesInPlanes(19, 3, 3))
+        ));
+    }
+
+    /// Check that a well-formed web is valid, and that each structural problem is caught.
+    #[test]
+    fn test_validate() {
+        use crate::ops::err::Error;
+
+        let web = web_from_cangle_fn(|_| 1.0);
+        assert!(web.validate().is_ok());
+        assert!(PhotometricWeb::new().validate().is_ok());
+
+        // Planes starting part way around the circle may wrap once.
+        let mut planes = web.planes().to_vec();
+        planes.rotate_left(9);
+        let mut wrapped = PhotometricWeb::new();
+        wrapped.set_planes(planes);
+        assert!(wrapped.validate().is_ok());
+
+        // A plane with more intensities than angles.
+        let mut planes = web.planes().to_vec();
+        planes[3].set_intensities(vec![1.0; 20]);
+        let mut invalid = PhotometricWeb::new();
+        invalid.set_planes(planes);
+        assert!(matches!(
+            invalid.validate(),
+            Err(Error::InconsistentIntensitiesInPlane(19, 20))
+        ));
+
+        // A plane with a different number of gamma samples.
+        let mut planes = web.planes().to_vec();
+        planes[5].set_angles_degrees(&[0.0, 90.0, 180.0]);
+        planes[5].set_intensities(vec![1.0; 3]);
+        let mut invalid = PhotometricWeb::new();
+        invalid.set_planes(planes);
+        assert!(matches!(
+            invalid.validate(),
+            Err(Error::InconsistentSamplesInPlanes(19, 3, 5))
+        ));
+
+        // Planes out of order. These are sorted by `set_planes`, so are swapped afterwards.
+        let mut invalid = PhotometricWeb::new();
+        invalid.set_planes(web.planes().to_vec());
+        invalid.mut_planes().swap(10, 11);
+        assert!(matches!(
+            invalid.validate(),
+            Err(Error::PlaneAnglesNotIncreasing(_))
+        ));
+
+        // A repeated plane angle.
+        let mut planes = web.planes().to_vec();
+        let repeated_angle = planes[1].angle();
+        planes[2].set_angle(repeated_angle);
+        let mut invalid = PhotometricWeb::new();
+        invalid.set_planes(planes);
+        assert!(matches!(
+            invalid.validate(),
+            Err(Error::PlaneAnglesNotIncreasing(2))
+        ));
+    }
+
+    /// Check that two halves of a web can be stitched back into the full web.
+    #[test]
+    fn test_merge() {
+        let full = web_from_cangle_fn(|c| 1.0 + 0.5 * c.cos());
+        let mut front = PhotometricWeb::new();
+        front.set_planes(full.planes()[..18].to_vec());
+        let mut back = PhotometricWeb::new();
+        back.set_planes(full.planes()[18..].to_vec());
+
+        // The order of the inputs should not matter.
+        match back.merge(&front) {
+            Ok(merged) => {
+                assert_eq!(merged.n_planes(), 36);
+                for (pl, expected) in merged.planes().iter().zip(full.planes()) {
+                    assert_abs_diff_eq!(pl.angle(), expected.angle(), epsilon = 1E-9);
+                    assert_eq!(pl.intensities(), expected.intensities());
+                }
+                assert_abs_diff_eq!(
+                    merged.total_intensity(),
+                    full.total_intensity(),
+                    epsilon = 1E-9
+                );
+            }
+            Err(e) => panic!("Merge error: {}", e),
+        }
+
+        // Merging a web with itself duplicates every azimuth.
+        assert!(matches!(
+            front.merge(&front),
+            Err(crate::ops::err::Error::DuplicatePlaneAngle(_))
+        ));
+
+        // The gamma angles must match.
+        let mut coarse = PhotometricWeb::new();
+        let mut plane = back.planes()[0].clone();
+        plane.set_angles_degrees(&[0.0, 90.0, 180.0]);
+        plane.set_intensities(vec![1.0, 1.0, 1.0]);
+        coarse.set_planes(vec![plane]);
+        assert!(matches!(
+            front.merge(&coarse),
+            Err(crate::ops::err::Error::InconsistentPlaneAngles)
+        ));
+    }
+
+    /// Check that webs differing only by float noise compare equal, but only to within the given epsilon.
+    #[test]
+    fn test_approx_eq() {
+        let web = web_from_cangle_fn(|c| 1.0 + c.cos());
+        let mut noisy = PhotometricWeb::new();
+        noisy.set_planes(
+            web.planes()
+                .iter()
+                .map(|pl| {
+                    let mut plane = pl.clone();
+                    plane.set_intensities(
+                        pl.intensities()
+                            .iter()
+                            .map(|int| int + 1E-9)
+                            .collect::<Vec<f64>>(),
+                    );
+                    plane
+                })
+                .collect::<Vec<Plane>>(),
+        );
 
-        let intensities: Vec<f64> = pl2.unwrap().intensities()
-            .into_iter()
-            .rev()
-            .chain(pl1.unwrap().intensities())
-            .map(|val| *val)
-            .collect();
+        assert!(web.approx_eq(&web, 0.0));
+        assert!(web.approx_eq(&noisy, 1E-6));
+        assert!(!web.approx_eq(&noisy, 1E-12));
+        assert!(!web.approx_eq(&PhotometricWeb::new(), 1E-6));
+    }
 
-        Some((angles, intensities))
+    /// Check that negative intensities are clamped to zero, leaving the positive planes untouched.
+    #[test]
+    fn test_clamp_negative() {
+        let mut web = web_from_cangle_fn(|c| if c < PI { 1.0 } else { -1.0 });
+        web.clamp_negative();
+        for pl in web.planes() {
+            if pl.angle() < PI {
+                assert!(pl.intensities().iter().all(|intens| *intens >= 0.0));
+                assert_abs_diff_eq!(pl.intensities()[0], 2.0, epsilon = 1E-12);
+            } else {
+                assert!(pl.intensities().iter().all(|intens| *intens == 0.0));
+            }
+        }
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use crate::util::geom::degrees_to_radians;
+    /// Check that clearing a populated web leaves it empty.
+    #[test]
+    fn test_clear() {
+        let mut web = web_from_cangle_fn(|_| 1.0);
+        assert!(web.total_intensity() > 0.0);
 
-    use super::{PhotometricWeb, Plane};
-    use approx::assert_abs_diff_eq;
-    use std::f64::consts::PI;
+        web.clear();
+        assert_eq!(web.n_planes(), 0);
+        assert_eq!(web.total_intensity(), 0.0);
+    }
 
-    /// Check that by default, our integrated intensity is zero.
+    /// A spherically symmetric Gaussian beam, $I = \exp(-\gamma^2 / 2 \sigma^2)$, falls to half of its peak
+    /// at $\gamma = \sigma \sqrt{2 \ln 2}$. For a narrow beam $\sin(\gamma) \approx \gamma$, so the fraction
+    /// of the flux within this cone is $1 - \exp(-\ln 2) = 0.5$.
     #[test]
-    fn test_integrate_zero() {
-        let web = PhotometricWeb::new();
-        let res = web.total_intensity();
-        assert_eq!(res, 0.0);
+    fn test_flux_within_beam_gaussian() {
+        let sigma = degrees_to_radians(10.0);
+        let mut plane = Plane::new();
+        plane.set_angles_degrees(&(0..361).map(|ang_i| 0.5 * ang_i as f64).collect::<Vec<f64>>());
+        plane.set_intensities(
+            plane
+                .angles()
+                .iter()
+                .map(|gamma| (-gamma * gamma / (2.0 * sigma * sigma)).exp())
+                .collect::<Vec<f64>>(),
+        );
+
+        let mut web = PhotometricWeb::new();
+        web.set_planes(vec![plane]);
+
+        let half_angle = sigma * (2.0 * 2.0_f64.ln()).sqrt();
+        assert_abs_diff_eq!(web.beam_angle(), 2.0 * half_angle, epsilon = 1E-3);
+        assert_abs_diff_eq!(web.flux_within_beam(), 0.5, epsilon = 1E-2);
+
+        // An empty web has no beam.
+        assert_eq!(PhotometricWeb::new().flux_within_beam(), 0.0);
     }
 
     /// In this case, I am filling the array with a constant of 1.0, which simplifies the integral for each plane
@@ -146,7 +1867,6 @@ mod tests {
         plane.set_angle(0.0);
         plane.set_angles_degrees(
             &(0..181)
-                .into_iter()
                 .map(|ang_i| ang_i as f64)
                 .collect::<Vec<f64>>(),
         );
@@ -162,6 +1882,41 @@ mod tests {
         assert_abs_diff_eq!(int, 4.0 * PI, epsilon = (4.0 * PI) * 1E-4);
     }
 
+    /// The same as the `test_integrate_spherically_symmetric` test case, but only measured over the lower hemisphere
+    /// (0 -> 90 degrees), as would be the case for a downlight. The integral of $\sin(\phi)$ over this range is 1.0,
+    /// so we expect $2 \pi$ from the spherically symmetric web, half of the full sphere.
+    #[test]
+    fn test_total_flux_spherically_symmetric_lower_hemisphere() {
+        let mut plane = Plane::new();
+        plane.set_angle(0.0);
+        plane.set_angles_degrees(&(0..91).map(|ang_i| ang_i as f64).collect::<Vec<f64>>());
+        plane.set_intensities(plane.angles().iter().map(|_| 1.0).collect::<Vec<f64>>());
+
+        let mut web = PhotometricWeb::new();
+        web.set_planes(vec![plane]);
+        assert_abs_diff_eq!(web.total_flux(), 2.0 * PI, epsilon = (2.0 * PI) * 1E-4);
+
+        // Check this is half of the result for the full sphere.
+        let mut plane = Plane::new();
+        plane.set_angles_degrees(&(0..181).map(|ang_i| ang_i as f64).collect::<Vec<f64>>());
+        plane.set_intensities(plane.angles().iter().map(|_| 1.0).collect::<Vec<f64>>());
+        let mut full_web = PhotometricWeb::new();
+        full_web.set_planes(vec![plane]);
+        assert_abs_diff_eq!(full_web.total_flux(), 4.0 * PI, epsilon = (4.0 * PI) * 1E-4);
+        assert_abs_diff_eq!(
+            web.total_flux(),
+            0.5 * full_web.total_flux(),
+            epsilon = (2.0 * PI) * 1E-4
+        );
+
+        // The full sphere splits evenly between the hemispheres, and the lower web is all downward.
+        let (downward, upward) = full_web.hemisphere_flux();
+        assert_abs_diff_eq!(downward, 2.0 * PI, epsilon = (2.0 * PI) * 1E-4);
+        assert_abs_diff_eq!(upward, 2.0 * PI, epsilon = (2.0 * PI) * 1E-4);
+        let (downward, _) = web.hemisphere_flux();
+        assert_abs_diff_eq!(downward, web.total_flux(), epsilon = (2.0 * PI) * 1E-4);
+    }
+
     /// This the same as the `test_integrate_spherically_symmetric` test case, however with a series of planes as opposed to spherical symmetry.
     /// I am filling the array with a constant of 1.0, which simplifies the integral for each plane
     /// to being $\int^{\pi}_{0} \sin(\phi) d\phi$. Once fully integrated and substituted, this will result in a final
@@ -173,7 +1928,6 @@ mod tests {
         plane.set_angle(0.0);
         plane.set_angles_degrees(
             &(0..181)
-                .into_iter()
                 .map(|ang_i| ang_i as f64)
                 .collect::<Vec<f64>>(),
         );
@@ -183,7 +1937,6 @@ mod tests {
         web.set_planes(
             (0..360)
                 .step_by(10)
-                .into_iter()
                 .map(|ang_deg| {
                     let mut new_plane = plane.clone();
                     new_plane.set_angle_degrees(ang_deg as f64);
@@ -209,7 +1962,6 @@ mod tests {
         plane.set_angle(0.0);
         plane.set_angles_degrees(
             &(0..181)
-                .into_iter()
                 .map(|ang_i| ang_i as f64)
                 .collect::<Vec<f64>>(),
         );
@@ -219,7 +1971,6 @@ mod tests {
         web.set_planes(
             (0..360)
                 .step_by(10)
-                .into_iter()
                 .map(|ang_deg| {
                     let mut new_plane = plane.clone();
                     new_plane.set_angle_degrees(ang_deg as f64);
@@ -258,7 +2009,6 @@ mod tests {
         plane.set_angle(0.0);
         plane.set_angles_degrees(
             &(0..181)
-                .into_iter()
                 .map(|ang_i| ang_i as f64)
                 .collect::<Vec<f64>>(),
         );
@@ -278,4 +2028,778 @@ mod tests {
         assert_eq!(lp.angle(), degrees_to_radians(0.));
         assert_eq!(up.angle(), degrees_to_radians(0.));
     }
+
+    /// Check that webs constructed with known symmetries are detected as such.
+    #[test]
+    fn test_detect_symmetry() {
+        let tol = 1E-9;
+        assert_eq!(
+            PhotometricWeb::new().detect_symmetry(tol),
+            EulumdatSymmetry::NoSymmetry
+        );
+        assert_eq!(
+            web_from_cangle_fn(|_| 1.0).detect_symmetry(tol),
+            EulumdatSymmetry::AboutVerticalAxis
+        );
+        assert_eq!(
+            web_from_cangle_fn(|c| 2.0 + c.cos().powi(2)).detect_symmetry(tol),
+            EulumdatSymmetry::C0C180C90C270Plane
+        );
+        assert_eq!(
+            web_from_cangle_fn(|c| 2.0 + c.cos()).detect_symmetry(tol),
+            EulumdatSymmetry::C0C180Plane
+        );
+        assert_eq!(
+            web_from_cangle_fn(|c| 2.0 + c.sin()).detect_symmetry(tol),
+            EulumdatSymmetry::C90C270Plane
+        );
+        assert_eq!(
+            web_from_cangle_fn(|c| c).detect_symmetry(tol),
+            EulumdatSymmetry::NoSymmetry
+        );
+    }
+
+    /// Check that small perturbations are only tolerated within the requested tolerance.
+    #[test]
+    fn test_detect_symmetry_tolerance() {
+        let web = web_from_cangle_fn(|c| 2.0 + c.cos() + 1E-6 * c.sin());
+        assert_eq!(web.detect_symmetry(1E-3), EulumdatSymmetry::C0C180Plane);
+        assert_eq!(web.detect_symmetry(1E-9), EulumdatSymmetry::NoSymmetry);
+    }
+
+    /// Check that collapsing a mirrored quadrant web recovers the original quadrant.
+    #[test]
+    fn test_collapse_symmetry_round_trip() {
+        let quadrant: Vec<Plane> = web_from_cangle_fn(|c| 2.0 + c.cos().powi(2))
+            .planes()
+            .iter()
+            .filter(|pl| pl.angle_deg() <= 90.0 + 1E-6)
+            .cloned()
+            .collect();
+        assert_eq!(quadrant.len(), 10);
+
+        let mut full = PhotometricWeb::new();
+        full.set_planes(mirror_first_hemisphere(&mirror_first_quadrant(&quadrant)));
+        assert_eq!(full.n_planes(), 36);
+
+        let collapsed = full
+            .collapse_symmetry(EulumdatSymmetry::C0C180C90C270Plane)
+            .unwrap();
+        assert_eq!(collapsed.n_planes(), quadrant.len());
+        for (orig, col) in quadrant.iter().zip(collapsed.planes()) {
+            assert_abs_diff_eq!(orig.angle(), col.angle(), epsilon = 1E-9);
+            assert_eq!(orig.intensities(), col.intensities());
+        }
+    }
+
+    /// Check that the mirrored copies of a quadrant are not counted as unique planes.
+    #[test]
+    fn test_unique_plane_count() {
+        let quadrant: Vec<Plane> = web_from_cangle_fn(|c| 2.0 + c.cos().powi(2))
+            .planes()
+            .iter()
+            .filter(|pl| pl.angle_deg() <= 90.0 + 1E-6)
+            .cloned()
+            .collect();
+        let mut full = PhotometricWeb::new();
+        full.set_planes(mirror_first_hemisphere(&mirror_first_quadrant(&quadrant)));
+        assert_eq!(full.n_planes(), 36);
+        assert_eq!(full.unique_plane_count(1E-9), quadrant.len());
+
+        assert_eq!(web_from_cangle_fn(|_| 1.0).unique_plane_count(1E-9), 1);
+        assert_eq!(web_from_cangle_fn(|c| c).unique_plane_count(1E-9), 36);
+        assert_eq!(PhotometricWeb::new().unique_plane_count(1E-9), 0);
+    }
+
+    /// Check that we can't collapse a web into a symmetry that it doesn't satisfy.
+    #[test]
+    fn test_collapse_symmetry_unsatisfied() {
+        let web = web_from_cangle_fn(|c| 2.0 + c.cos());
+        assert!(web
+            .collapse_symmetry(EulumdatSymmetry::C0C180C90C270Plane)
+            .is_err());
+        assert_eq!(
+            web.collapse_symmetry(EulumdatSymmetry::C0C180Plane)
+                .unwrap()
+                .n_planes(),
+            19
+        );
+        assert_eq!(
+            web.collapse_symmetry(EulumdatSymmetry::NoSymmetry)
+                .unwrap()
+                .n_planes(),
+            36
+        );
+    }
+
+    /// Check that the flux centroid points towards a dominant plane, and is undefined for a symmetric web.
+    #[test]
+    fn test_flux_centroid_cangle() {
+        let web = web_from_cangle_fn(|c| if (c - PI / 2.0).abs() < 1E-6 { 10.0 } else { 1.0 });
+        assert_abs_diff_eq!(web.flux_centroid_cangle(), PI / 2.0, epsilon = 1E-6);
+
+        // Make sure that we handle the wrap around 2 pi.
+        let web = web_from_cangle_fn(|c| 2.0 + (c - degrees_to_radians(350.0)).cos());
+        assert_abs_diff_eq!(
+            web.flux_centroid_cangle(),
+            degrees_to_radians(350.0),
+            epsilon = 1E-6
+        );
+
+        // Balanced distributions have no dominant direction.
+        assert!(web_from_cangle_fn(|_| 1.0).flux_centroid_cangle().is_nan());
+        assert!(PhotometricWeb::new().flux_centroid_cangle().is_nan());
+    }
+
+    #[test]
+    fn test_cutoff_classification() {
+        let web_from_gamma_fn = |func: fn(f64) -> f64| {
+            let mut plane = Plane::new();
+            plane.set_angles_degrees(
+                &(0..181)
+                    .step_by(5)
+                    .map(|ang| ang as f64)
+                    .collect::<Vec<f64>>(),
+            );
+            plane.set_intensities(
+                plane
+                    .angles()
+                    .iter()
+                    .map(|gamma| func(*gamma))
+                    .collect::<Vec<f64>>(),
+            );
+            let mut web = PhotometricWeb::new();
+            web.set_planes(vec![plane]);
+            web.set_total_rated_lumens(10000.0);
+            web
+        };
+
+        // A distribution that is sharply cut off well below the horizontal.
+        let web = web_from_gamma_fn(|gamma| {
+            if gamma < degrees_to_radians(70.0) {
+                1000.0
+            } else {
+                0.0
+            }
+        });
+        assert_eq!(web.cutoff_classification(), CutoffClass::FullCutoff);
+
+        // A small amount of light at the horizontal is permitted by the lesser classes.
+        let web = web_from_gamma_fn(|gamma| if gamma <= PI / 2.0 { 200.0 } else { 0.0 });
+        assert_eq!(web.cutoff_classification(), CutoffClass::Cutoff);
+        let web = web_from_gamma_fn(|gamma| if gamma <= PI / 2.0 { 400.0 } else { 0.0 });
+        assert_eq!(web.cutoff_classification(), CutoffClass::SemiCutoff);
+
+        // A broad distribution that emits in all directions.
+        let web = web_from_gamma_fn(|_| 1000.0);
+        assert_eq!(web.cutoff_classification(), CutoffClass::NonCutoff);
+    }
+
+    #[test]
+    fn test_spacing_criterion() {
+        // Lambertian in C0 / C180 falls to half at 60 degrees, and cosine squared in C90 / C270 at 45 degrees.
+        let mut web = PhotometricWeb::new();
+        web.set_planes(
+            [0.0, 90.0, 180.0, 270.0]
+                .iter()
+                .map(|angle| {
+                    let power = 1 + (*angle as i32 / 90) % 2;
+                    let mut plane = Plane::new();
+                    plane.set_angle_degrees(*angle);
+                    plane.set_angles_degrees(
+                        &(0..91)
+                            .step_by(10)
+                            .map(|ang| ang as f64)
+                            .collect::<Vec<f64>>(),
+                    );
+                    plane.set_intensities(
+                        plane
+                            .angles()
+                            .iter()
+                            .map(|gamma| 1000.0 * gamma.cos().powi(power))
+                            .collect::<Vec<f64>>(),
+                    );
+                    plane
+                })
+                .collect(),
+        );
+
+        assert_abs_diff_eq!(
+            web.spacing_criterion(0.0),
+            2.0 * 3.0_f64.sqrt(),
+            epsilon = 1E-6
+        );
+        assert_abs_diff_eq!(web.spacing_criterion(PI / 2.0), 2.0, epsilon = 1E-6);
+        assert!(PhotometricWeb::new().spacing_criterion(0.0).is_nan());
+    }
+
+    #[test]
+    fn test_normalize_angles() {
+        // Mirroring planes about pi can leave angles outside of [0, 2 pi), as here.
+        let mut web = PhotometricWeb::new();
+        web.set_planes(
+            [370.0, 90.0, 180.0, -90.0]
+                .iter()
+                .map(|&ang_deg| {
+                    let mut plane = Plane::new();
+                    plane.set_angle_degrees(ang_deg);
+                    plane.set_angles_degrees(&[0.0, 90.0, 180.0]);
+                    plane.set_intensities(vec![ang_deg; 3]);
+                    plane
+                })
+                .collect::<Vec<Plane>>(),
+        );
+
+        let angles = web
+            .planes()
+            .iter()
+            .map(|pl| pl.angle_deg())
+            .collect::<Vec<f64>>();
+        let expected = [10.0, 90.0, 180.0, 270.0];
+        assert_eq!(angles.len(), expected.len());
+        for (angle, expected) in angles.iter().zip(expected.iter()) {
+            assert_abs_diff_eq!(angle, expected, epsilon = 1E-9);
+        }
+        assert_eq!(
+            web.plane_at_angle(degrees_to_radians(10.0))
+                .unwrap()
+                .intensities()[0],
+            370.0
+        );
+        assert_abs_diff_eq!(
+            web.planes()[0].width().total(),
+            degrees_to_radians(90.0),
+            epsilon = 1E-9
+        );
+
+        // An angle a hair below 2 pi wraps to zero.
+        web.mut_planes()[3].set_angle(2.0 * PI - 1E-12);
+        web.normalize_angles();
+        assert_eq!(web.planes()[0].angle(), 0.0);
+    }
+
+    #[test]
+    fn test_average_luminance() {
+        let mut web = web_from_cangle_fn(|_| 1.0);
+        assert!(web.average_luminance(0.0, 0.0).is_none());
+
+        web.set_luminous_opening(IesLuminousOpening::Rectangular {
+            width: 0.5,
+            length: 0.2,
+        });
+        let gamma = degrees_to_radians(60.0);
+        assert_abs_diff_eq!(
+            web.average_luminance(0.0, 0.0).unwrap(),
+            2.0 / 0.1,
+            epsilon = 1E-9
+        );
+        assert_abs_diff_eq!(
+            web.average_luminance(0.0, gamma).unwrap(),
+            web.intensity_at(0.0, gamma) / 0.05,
+            epsilon = 1E-9
+        );
+
+        // A flat opening can't be seen from the horizontal.
+        assert!(web.average_luminance(0.0, PI / 2.0).is_none());
+        web.set_luminous_opening(IesLuminousOpening::Circular { diameter: 0.2 });
+        assert_abs_diff_eq!(
+            web.average_luminance(0.0, 0.0).unwrap(),
+            2.0 / (PI * 0.01),
+            epsilon = 1E-9
+        );
+    }
+
+    #[test]
+    fn test_intensities_at_gamma() {
+        let web = web_from_cangle_fn(|c| 1.0 + c);
+        let gamma = degrees_to_radians(70.0);
+        let intensities = web.intensities_at_gamma(gamma);
+        assert_eq!(intensities.len(), web.n_planes());
+        for ((c_angle, intens), pl) in intensities.iter().zip(web.planes()) {
+            assert_eq!(*c_angle, pl.angle());
+            assert_eq!(*intens, pl.intensities()[7]);
+        }
+    }
+
+    #[test]
+    fn test_intensity_at() {
+        let web = web_from_cangle_fn(|c| if c < PI { 1.0 } else { 2.0 });
+        let gamma = degrees_to_radians(45.0);
+        let expected = |scale: f64| {
+            scale * (1.0 + degrees_to_radians(40.0).cos() + 1.0 + degrees_to_radians(50.0).cos())
+                / 2.0
+        };
+
+        // Directly on a plane, and half way between two planes.
+        assert_abs_diff_eq!(web.intensity_at(0.0, gamma), expected(1.0), epsilon = 1E-12);
+        assert_abs_diff_eq!(
+            web.intensity_at(degrees_to_radians(175.0), gamma),
+            expected(1.5),
+            epsilon = 1E-12
+        );
+
+        // Between the last and first planes, wrapping around 2 pi.
+        assert_abs_diff_eq!(
+            web.intensity_at(degrees_to_radians(355.0), gamma),
+            expected(1.5),
+            epsilon = 1E-12
+        );
+        assert_eq!(PhotometricWeb::new().intensity_at(0.0, gamma), 0.0);
+    }
+
+    #[test]
+    fn test_centroid_direction() {
+        // A symmetric downlight should point straight down.
+        let (_, gamma) = web_from_cangle_fn(|_| 1.0).centroid_direction();
+        assert_abs_diff_eq!(gamma, 0.0, epsilon = 1E-6);
+
+        // Weighting the C90 plane tilts the centroid towards it, but not past the horizontal.
+        let web = web_from_cangle_fn(|c| if (c - PI / 2.0).abs() < 1E-6 { 10.0 } else { 1.0 });
+        let (c_angle, gamma) = web.centroid_direction();
+        assert_abs_diff_eq!(c_angle, PI / 2.0, epsilon = 1E-6);
+        assert!(gamma > 0.0 && gamma < PI / 2.0);
+
+        // An empty distribution has no direction.
+        assert_eq!(PhotometricWeb::new().centroid_direction(), (0.0, 0.0));
+        assert_eq!(web_from_cangle_fn(|_| 0.0).centroid_direction(), (0.0, 0.0));
+    }
+
+    #[test]
+    fn test_zonal_cavity_cu() {
+        let web = web_from_cangle_fn(|_| 1.0);
+        let lumens = web.total_flux();
+        let (downward, upward) = web.hemisphere_flux();
+        let (phi_d, phi_u) = (downward / lumens, upward / lumens);
+
+        // In an infinitely large room, the downward flux and the flux reflected from the ceiling all reach the
+        // work plane, inter-reflecting between the floor and ceiling cavities.
+        assert_abs_diff_eq!(
+            web.zonal_cavity_cu(0.0, (0.8, 0.5, 0.2)),
+            (phi_d + 0.8 * phi_u) / (1.0 - 0.8 * 0.2),
+            epsilon = 1E-9
+        );
+
+        // A room with black surfaces only receives the direct component of the downward flux.
+        let rcr = 2.0;
+        let direct = (0..9)
+            .map(|izone| {
+                let (a, b) = ZONAL_MULTIPLIER_CONSTANTS[izone];
+                (-a * f64::powf(rcr, b)).exp()
+                    * web.zonal_flux(
+                        degrees_to_radians(10.0 * izone as f64),
+                        degrees_to_radians(10.0 * (izone + 1) as f64),
+                    )
+            })
+            .sum::<f64>()
+            / lumens;
+        assert_abs_diff_eq!(
+            web.zonal_cavity_cu(rcr, (0.0, 0.0, 0.0)),
+            direct,
+            epsilon = 1E-9
+        );
+
+        // The utilisation falls as rooms become narrower, and rises with the wall reflectance.
+        let reflectances = (0.8, 0.5, 0.2);
+        assert!(web.zonal_cavity_cu(1.0, reflectances) > web.zonal_cavity_cu(5.0, reflectances));
+        assert!(web.zonal_cavity_cu(5.0, (0.8, 0.7, 0.2)) > web.zonal_cavity_cu(5.0, reflectances));
+        assert_eq!(PhotometricWeb::new().zonal_cavity_cu(1.0, reflectances), 0.0);
+    }
+
+    /// A worked example of the zonal cavity method, following the steps of the IES handbook procedure for a
+    /// luminaire emitting 1 cd uniformly into the lower hemisphere (2 pi lm, all of it downwards, with the lamp
+    /// lumens equal to the luminaire output) in a room with 80/50/20 ceiling cavity, wall and floor cavity
+    /// reflectances. The zonal lumens for the 10 degree zones are 2 pi (cos a - cos b):
+    ///   0.0955, 0.2835, 0.4629, 0.6282, 0.7744, 0.8972, 0.9926, 1.0579, 1.0911.
+    /// For each room cavity ratio, the zonal multipliers K, the direct ratio DR, the cavity coefficients C1-C3
+    /// and the resulting CU are:
+    ///   RCR 1: K = 1.000 0.960 0.932 0.905 0.873 0.827 0.730 0.527 0.122,
+    ///          DR = 0.6686, C1 = 0.3333, C2 = 0.2124, C3 = 1.0448, CU = 0.9876.
+    ///   RCR 2: K = 1.000 0.922 0.865 0.805 0.738 0.636 0.473 0.218 0.026,
+    ///          DR = 0.4987, C1 = 0.6667, C2 = 0.2079, C3 = 0.9443, CU = 0.8237.
+    ///   RCR 5: K = 1.000 0.820 0.684 0.545 0.415 0.242 0.095 0.008 0.000,
+    ///          DR = 0.2592, C1 = 1.6667, C2 = 0.2037, C3 = 0.8621, CU = 0.5197.
+    #[test]
+    fn test_zonal_cavity_cu_worked_example() {
+        let mut plane = Plane::new();
+        plane.set_angles_degrees(&(0..1801).map(|ang| ang as f64 / 10.0).collect::<Vec<f64>>());
+        plane.set_intensities(
+            plane
+                .angles()
+                .iter()
+                .map(|gamma| if *gamma < FRAC_PI_2 { 1.0 } else { 0.0 })
+                .collect::<Vec<f64>>(),
+        );
+        let mut web = PhotometricWeb::new();
+        web.set_planes(vec![plane]);
+        web.set_total_rated_lumens(2.0 * PI);
+
+        let zonal_lumens = [
+            0.0955, 0.2835, 0.4629, 0.6282, 0.7744, 0.8972, 0.9926, 1.0579, 1.0911,
+        ];
+        // The 80-90 degree zone loses half of the sample cell at the cut-off at 90 degrees.
+        for (izone, lumens) in zonal_lumens.iter().enumerate() {
+            let zone_flux = web.zonal_flux(
+                degrees_to_radians(10.0 * izone as f64),
+                degrees_to_radians(10.0 * (izone + 1) as f64),
+            );
+            assert_abs_diff_eq!(zone_flux, *lumens, epsilon = 1E-2);
+        }
+
+        let reflectances = (0.8, 0.5, 0.2);
+        for (rcr, cu) in [(1.0, 0.9876), (2.0, 0.8237), (5.0, 0.5197)] {
+            assert_abs_diff_eq!(web.zonal_cavity_cu(rcr, reflectances), cu, epsilon = 2E-3);
+        }
+    }
+
+    #[test]
+    fn test_display() {
+        let web = web_from_cangle_fn(|_| 1.0);
+        let summary = web.to_string();
+        assert!(summary.contains("36 planes"));
+        assert!(summary.contains(&format!("{:.2}", web.total_flux())));
+        assert!(summary.contains("gamma 0-180 deg"));
+        assert!(summary.contains("spherically symmetric: no"));
+        assert!(!summary.contains('\n'));
+
+        assert_eq!(
+            PhotometricWeb::new().to_string(),
+            "PhotometricWeb: 0 planes, no samples"
+        );
+
+        // The flux is labelled with the units given by integrating the intensities.
+        let mut web = web_from_cangle_fn(|_| 1.0);
+        for plane in web.mut_planes().iter_mut() {
+            plane.set_units(IntensityUnits::CandelaPerKilolumen);
+        }
+        assert!(web
+            .to_string()
+            .contains(&format!("total flux {:.2} lm/klm,", web.total_flux())));
+        web.mut_planes()[0].set_units(IntensityUnits::Candela);
+        assert!(web
+            .to_string()
+            .contains("total flux unknown (mixed units),"));
+    }
+
+    #[test]
+    fn test_apply_gamma_cutoff() {
+        let mut web = web_from_cangle_fn(|_| 1.0);
+        let cutoff = degrees_to_radians(30.0);
+        // The sample at the cutoff keeps its bin, which extends half way to the next sample at 40 degrees.
+        let bin_edge = degrees_to_radians(35.0);
+        let expected_flux = web.zonal_flux(0.0, bin_edge);
+        assert!(web.zonal_flux(bin_edge, PI) > 0.0);
+
+        web.apply_gamma_cutoff(cutoff);
+        assert_abs_diff_eq!(web.zonal_flux(bin_edge, PI), 0.0, epsilon = 1E-12);
+        assert_abs_diff_eq!(web.total_flux(), expected_flux, epsilon = 1E-12);
+    }
+
+    #[test]
+    fn test_plane_fluxes() {
+        // Every plane of a uniform web contributes equally.
+        let web = web_from_cangle_fn(|_| 1.0);
+        let fluxes = web.plane_fluxes();
+        assert_eq!(fluxes.len(), web.n_planes());
+        for flux in fluxes.iter() {
+            assert_abs_diff_eq!(*flux, fluxes[0], epsilon = 1E-12);
+        }
+        assert_abs_diff_eq!(
+            fluxes.iter().sum::<f64>(),
+            web.total_intensity(),
+            epsilon = 1E-12
+        );
+
+        // Weighting the C90 plane makes it dominate.
+        let web = web_from_cangle_fn(|c| if (c - PI / 2.0).abs() < 1E-6 { 10.0 } else { 1.0 });
+        assert_eq!(web.dominant_plane(), Some(9));
+        assert_eq!(PhotometricWeb::new().dominant_plane(), None);
+    }
+
+    #[test]
+    fn test_resample_azimuth() {
+        let mut symmetric = PhotometricWeb::new();
+        symmetric.set_planes(vec![web_from_cangle_fn(|_| 1.0).planes()[0].clone()]);
+        assert!(symmetric.is_spherically_symmetric());
+
+        // The number of planes, and so samples, scales with the number of steps.
+        let coarse = symmetric.resample_azimuth(8);
+        let fine = symmetric.resample_azimuth(DEFAULT_AZIMUTH_STEPS);
+        assert_eq!(coarse.n_planes(), 8);
+        assert_eq!(fine.n_planes(), 36);
+        assert_eq!(
+            fine.iter_samples().count(),
+            coarse.iter_samples().count() * 36 / 8
+        );
+
+        // Revolving the plane doesn't change the distribution.
+        for web in [&coarse, &fine] {
+            assert_abs_diff_eq!(
+                web.total_intensity(),
+                symmetric.total_intensity(),
+                epsilon = 1E-9
+            );
+            assert_eq!(
+                web.planes()[3].intensities(),
+                symmetric.planes()[0].intensities()
+            );
+        }
+
+        // Resampling a full web interpolates between its planes.
+        let web = web_from_cangle_fn(|c| 2.0 + c.cos());
+        let resampled = web.resample_azimuth(72);
+        assert_abs_diff_eq!(
+            resampled.planes()[1].intensities()[0],
+            web.intensity_at(degrees_to_radians(5.0), 0.0),
+            epsilon = 1E-12
+        );
+        assert_eq!(PhotometricWeb::new().resample_azimuth(8).n_planes(), 0);
+    }
+
+    #[test]
+    fn test_beam_solid_angle() {
+        // A uniform distribution fills the whole sphere.
+        let mut web = PhotometricWeb::new();
+        let mut plane = Plane::new();
+        plane.set_angles_degrees(&(0..181).map(|ang| ang as f64).collect::<Vec<f64>>());
+        plane.set_intensities(vec![10.0; 181]);
+        web.set_planes(vec![plane]);
+        assert_abs_diff_eq!(web.beam_solid_angle(), 4.0 * PI, epsilon = 1E-3);
+
+        // 1 + cos(gamma) has a total flux of 4 pi and a peak of 2, so fills half of the sphere.
+        let web = web_from_cangle_fn(|_| 1.0);
+        assert_abs_diff_eq!(
+            web.beam_solid_angle(),
+            web.total_intensity() / 2.0,
+            epsilon = 1E-12
+        );
+        assert_abs_diff_eq!(web.beam_solid_angle(), 2.0 * PI, epsilon = 1E-1);
+
+        assert_eq!(PhotometricWeb::new().beam_solid_angle(), 0.0);
+        assert_eq!(web_from_cangle_fn(|_| 0.0).beam_solid_angle(), 0.0);
+    }
+
+    #[test]
+    fn test_intensity() {
+        let web = web_from_cangle_fn(|_| 1.0);
+        assert_eq!(web.intensity(0, 0), Some(2.0));
+        assert_eq!(web.intensity(35, 18), Some(web.planes()[35].intensities()[18]));
+        assert_eq!(web.intensity(36, 0), None);
+        assert_eq!(web.intensity(0, 19), None);
+        assert_eq!(PhotometricWeb::new().intensity(0, 0), None);
+    }
+
+    #[test]
+    fn test_angle_grids() {
+        let web = web_from_cangle_fn(|_| 1.0);
+        let c_angles = web.c_angles();
+        let gamma_angles = web.gamma_angles();
+        assert_eq!(c_angles.len(), 36);
+        assert_eq!(gamma_angles.len(), 19);
+        for (i, c_angle) in c_angles.iter().enumerate() {
+            assert_abs_diff_eq!(
+                *c_angle,
+                degrees_to_radians(10.0 * i as f64),
+                epsilon = 1E-12
+            );
+        }
+        for (i, gamma) in gamma_angles.iter().enumerate() {
+            assert_abs_diff_eq!(*gamma, degrees_to_radians(10.0 * i as f64), epsilon = 1E-12);
+        }
+
+        assert!(PhotometricWeb::new().c_angles().is_empty());
+        assert!(PhotometricWeb::new().gamma_angles().is_empty());
+    }
+
+    /// Symmetrizing an almost symmetric web should make the symmetry detectable at a tight tolerance.
+    #[test]
+    fn test_symmetrize() {
+        let tol = 1E-9;
+        let mut web = web_from_cangle_fn(|c| 2.0 + c.cos() + 1E-3 * c.sin());
+        assert_eq!(web.detect_symmetry(tol), EulumdatSymmetry::NoSymmetry);
+        web.symmetrize(EulumdatSymmetry::C0C180Plane);
+        assert_eq!(web.detect_symmetry(tol), EulumdatSymmetry::C0C180Plane);
+
+        let mut web = web_from_cangle_fn(|c| 2.0 + c.cos().powi(2) + 1E-3 * c);
+        web.symmetrize(EulumdatSymmetry::C0C180C90C270Plane);
+        assert_eq!(
+            web.detect_symmetry(tol),
+            EulumdatSymmetry::C0C180C90C270Plane
+        );
+
+        let mut web = web_from_cangle_fn(|c| 2.0 + c.sin());
+        web.symmetrize(EulumdatSymmetry::AboutVerticalAxis);
+        assert_eq!(
+            web.detect_symmetry(tol),
+            EulumdatSymmetry::AboutVerticalAxis
+        );
+
+        let mut web = web_from_cangle_fn(|c| c);
+        let original = web.planes().to_vec();
+        web.symmetrize(EulumdatSymmetry::NoSymmetry);
+        assert!(web
+            .planes()
+            .iter()
+            .zip(original.iter())
+            .all(|(pl, orig)| pl.approx_eq(orig, 0.0)));
+    }
+
+    /// Downsampling a finely sampled web should give the requested grid while preserving the integrated flux.
+    #[test]
+    fn test_downsample() {
+        let mut web = PhotometricWeb::new();
+        web.set_planes(
+            (0..36)
+                .map(|iplane| {
+                    let mut plane = Plane::new();
+                    plane.set_angle_degrees(iplane as f64 * 10.0);
+                    plane.set_angles_degrees(&(0..181).map(|ang| ang as f64).collect::<Vec<f64>>());
+                    let scale = 2.0 + plane.angle().cos();
+                    plane.set_intensities(
+                        plane
+                            .angles()
+                            .iter()
+                            .map(|gamma| scale * (1.0 + gamma.cos()).powi(2))
+                            .collect::<Vec<f64>>(),
+                    );
+                    plane
+                })
+                .collect(),
+        );
+
+        let small = web.downsample(37, 12);
+        assert_eq!(small.n_planes(), 12);
+        assert!(small.planes().iter().all(|pl| pl.n_samples() == 37));
+        assert_abs_diff_eq!(
+            small.total_intensity() / web.total_intensity(),
+            1.0,
+            epsilon = 0.01
+        );
+
+        assert_eq!(PhotometricWeb::new().downsample(37, 12).n_planes(), 0);
+    }
+
+    /// Grid nodes that coincide with the stored samples should return the stored intensities.
+    #[test]
+    fn test_to_grid() {
+        let web = web_from_cangle_fn(|c| 2.0 + c.cos() + 0.5 * c.sin());
+        let grid = web.to_grid(36, 19);
+        assert_eq!(grid.len(), 36);
+        for (row, plane) in grid.iter().zip(web.planes()) {
+            assert_eq!(row.len(), 19);
+            for (value, intens) in row.iter().zip(plane.intensities()) {
+                assert_abs_diff_eq!(value, intens, epsilon = 1E-9);
+            }
+        }
+
+        assert!(web.to_grid(0, 19).is_empty());
+        assert!(PhotometricWeb::new()
+            .to_grid(4, 3)
+            .iter()
+            .all(|row| row == &vec![0.0; 3]));
+    }
+
+    /// The luminous area heights should be interpolated between the quadrants, and add the sides to the
+    /// area seen from the horizontal.
+    #[test]
+    fn test_luminous_area_heights() {
+        let mut web = web_from_cangle_fn(|_| 1.0);
+        assert!(web.luminous_area_height_at(0.0).is_none());
+
+        web.set_luminous_opening(IesLuminousOpening::Rectangular {
+            width: 0.5,
+            length: 0.2,
+        });
+        web.set_luminous_area_heights([0.1, 0.2, 0.3, 0.4]);
+        assert_abs_diff_eq!(web.luminous_area_height_at(0.0).unwrap(), 0.1);
+        assert_abs_diff_eq!(
+            web.luminous_area_height_at(PI / 4.0).unwrap(),
+            0.15,
+            epsilon = 1E-12
+        );
+        assert_abs_diff_eq!(
+            web.luminous_area_height_at(7.0 * PI / 4.0).unwrap(),
+            0.25,
+            epsilon = 1E-12
+        );
+
+        // From the horizontal, only the side in that direction is visible.
+        let intens = web.intensity_at(0.0, PI / 2.0);
+        assert_abs_diff_eq!(
+            web.average_luminance(0.0, PI / 2.0).unwrap(),
+            intens / (0.1 * 0.5),
+            epsilon = 1E-9
+        );
+        assert_abs_diff_eq!(
+            web.average_luminance(PI / 2.0, PI / 2.0).unwrap(),
+            web.intensity_at(PI / 2.0, PI / 2.0) / (0.2 * 0.2),
+            epsilon = 1E-9
+        );
+    }
+
+    /// The flux in the four quadrants should sum to the total, and sectors should take a share of each plane.
+    #[test]
+    fn test_flux_in_region() {
+        let web = web_from_cangle_fn(|c| 2.0 + c.cos() + 0.5 * c.sin());
+        let quadrants: f64 = (0..4)
+            .map(|iquad| {
+                let c_min = iquad as f64 * FRAC_PI_2;
+                web.flux_in_region(c_min, c_min + FRAC_PI_2, 0.0, PI)
+            })
+            .sum();
+        assert_abs_diff_eq!(quadrants, web.total_intensity(), epsilon = 1E-9);
+        assert_abs_diff_eq!(
+            web.flux_in_region(0.0, 2.0 * PI, 0.0, FRAC_PI_2),
+            web.zonal_flux(0.0, FRAC_PI_2),
+            epsilon = 1E-9
+        );
+
+        // A sector spanning C0 is the sum of the sectors either side of it.
+        assert_abs_diff_eq!(
+            web.flux_in_region(-FRAC_PI_2, FRAC_PI_2, 0.0, PI),
+            web.flux_in_region(3.0 * FRAC_PI_2, 2.0 * PI, 0.0, PI)
+                + web.flux_in_region(0.0, FRAC_PI_2, 0.0, PI),
+            epsilon = 1E-9
+        );
+
+        // A spherically symmetric web emits evenly into every sector.
+        let mut plane = Plane::new();
+        plane.set_angles_degrees(&[0.0, 90.0, 180.0]);
+        plane.set_intensities(vec![1.0, 1.0, 1.0]);
+        let mut sym = PhotometricWeb::new();
+        sym.set_planes(vec![plane]);
+        assert_abs_diff_eq!(
+            sym.flux_in_region(0.3, 0.3 + FRAC_PI_2, 0.0, PI),
+            0.25 * sym.total_intensity(),
+            epsilon = 1E-9
+        );
+    }
+
+    /// Metadata that is missing should be copied across, while metadata that is already set is kept.
+    #[test]
+    fn test_copy_metadata_from() {
+        let mut source = web_from_cangle_fn(|_| 1.0);
+        source.set_input_watts(50.0);
+        source.set_total_rated_lumens(4000.0);
+        source.set_luminous_opening(IesLuminousOpening::Circular { diameter: 0.2 });
+        source.set_luminous_area_heights([0.1, 0.2, 0.3, 0.4]);
+
+        let mut web = PhotometricWeb::new();
+        web.set_input_watts(30.0);
+        web.copy_metadata_from(&source);
+        assert_eq!(web.input_watts(), Some(30.0));
+        assert_eq!(web.total_rated_lumens(), Some(4000.0));
+        assert_eq!(
+            web.luminous_opening(),
+            Some(&IesLuminousOpening::Circular { diameter: 0.2 })
+        );
+        assert_eq!(web.luminous_area_heights(), Some(&[0.1, 0.2, 0.3, 0.4]));
+
+        // Derived webs carry all of the metadata across.
+        let resampled = source.resample_azimuth(12);
+        assert_eq!(resampled.input_watts(), Some(50.0));
+        assert_eq!(
+            resampled.luminous_area_heights(),
+            source.luminous_area_heights()
+        );
+    }
 }
@@ -1,13 +1,37 @@
-use std::{default::Default, path::Path};
+use std::{collections::HashMap, default::Default, path::Path};
 
 use super::{PhotometricWeb, PhotometricWebReader};
-use crate::{err::Error, io};
+use crate::{
+    err::Error,
+    io::{self, Diagnostics},
+    ops,
+};
+
+/// A function that creates a reader for a registered file format.
+pub type ReaderFactory = Box<dyn Fn() -> Box<dyn PhotometricWebReader>>;
 
 /// The object that builds `PhotometricWeb` objects.
 /// This can instantiate from values, or read from a file of one of the supported types.
-#[derive(Default)]
 pub struct PhotometricWebBuilder {
     input_file: Option<Box<Path>>,
+    /// A factor to multiply all of the intensities by once the web has been read.
+    intensity_scale: Option<f64>,
+    /// The readers for each supported file format, keyed by lowercase file extension.
+    readers: HashMap<String, ReaderFactory>,
+}
+
+impl Default for PhotometricWebBuilder {
+    /// Creates a builder with the built-in IES and EULUMDAT readers registered.
+    fn default() -> Self {
+        Self {
+            input_file: None,
+            intensity_scale: None,
+            readers: HashMap::new(),
+        }
+        .register_format("ldt", || Box::new(io::eulumdat::EulumdatFile::new()))
+        .register_format("eul", || Box::new(io::eulumdat::EulumdatFile::new()))
+        .register_format("ies", || Box::new(io::ies::IesFile::new()))
+    }
 }
 
 impl PhotometricWebBuilder {
@@ -19,39 +43,225 @@ impl PhotometricWebBuilder {
         }
     }
 
-    /// Attempts to return the correct file parser for the given provided file.
+    /// Multiplies all of the plane intensities by `factor` when the web is built.
+    /// This is applied after the file has been read, in addition to any multiplier in the file itself.
+    pub fn with_intensity_scale(self, factor: f64) -> Self {
+        Self {
+            intensity_scale: Some(factor),
+            ..self
+        }
+    }
+
+    /// Registers a reader for files with the given extension (without the leading dot), so that new formats can
+    /// be read without changing the builder. The factory is called to create a reader each time a file is read.
+    /// Registering an extension that is already registered, including the built-in formats, replaces its reader.
+    pub fn register_format<F>(mut self, ext: &str, factory: F) -> Self
+    where
+        F: Fn() -> Box<dyn PhotometricWebReader> + 'static,
+    {
+        self.readers.insert(
+            ext.trim_start_matches('.').to_lowercase(),
+            Box::new(factory),
+        );
+        self
+    }
+
+    /// Attempts to return the correct file parser for the given provided file, from the registered formats.
     /// Returns a boxed reader if valid, else an `Error::InvalidFileType` error is resturned.
-    pub fn get_file_parser(path: &Path) -> Result<Box<dyn PhotometricWebReader>, Error> {
+    pub fn get_file_parser(&self, path: &Path) -> Result<Box<dyn PhotometricWebReader>, Error> {
         match path.extension() {
             None => Err(Error::InvalidFileType(String::new())),
             Some(file_ext) => match file_ext.to_str() {
                 None => Err(Error::InvalidFileType(String::new())),
-                Some(file_ext) => match file_ext {
-                    "ldt" => Ok(Box::<io::eulumdat::EulumdatFile>::new(
-                        io::eulumdat::EulumdatFile::new(),
-                    )),
-                    "eul" => Ok(Box::<io::eulumdat::EulumdatFile>::new(
-                        io::eulumdat::EulumdatFile::new(),
-                    )),
-                    "ies" => Ok(Box::new(io::ies::IesFile::new())),
-                    _ => Err(Error::InvalidFileType(file_ext.to_owned())),
+                // Extensions are matched case-insensitively, as files from Windows tools are often uppercase.
+                Some(file_ext) => match self.readers.get(&file_ext.to_lowercase()) {
+                    Some(factory) => Ok(factory()),
+                    None => Err(Error::InvalidFileType(file_ext.to_owned())),
                 },
             },
         }
     }
 
+    /// Reads the web from a file using the reader registered for its extension.
+    /// With the `gzip` feature, gzip-compressed files are detected from their magic bytes and decompressed first,
+    /// choosing the reader from the extension inside the compressed file, such as `ies` for `lamp.ies.gz`.
+    /// Any failure reading the file is wrapped in an `Error::BuildError`, with the cause as its source.
+    fn read_file(&self, path: &Path) -> Result<(PhotometricWeb, Diagnostics), Error> {
+        #[cfg(feature = "gzip")]
+        {
+            let rdr = self.get_file_parser(&io::gzip::inner_path(path))?;
+            match io::gzip::read_if_gzipped(path).map_err(Error::build_error)? {
+                Some(contents) => rdr.read_str_with_diagnostics(&contents),
+                None => rdr.read_with_diagnostics(path),
+            }
+            .map_err(Error::build_error)
+        }
+
+        #[cfg(not(feature = "gzip"))]
+        {
+            let rdr = self.get_file_parser(path)?;
+            rdr.read_with_diagnostics(path).map_err(Error::build_error)
+        }
+    }
+
     /// Attempts to build the photometric web from the provided information.
     pub fn build(&self) -> Result<PhotometricWeb, Error> {
-        match &self.input_file {
-            Some(box_path) => {
-                let rdr = Self::get_file_parser(&*box_path)?;
-                let phot = rdr.read(&*box_path)?;
-                Ok(phot)
-            }
-            None => {
-                let phot = PhotometricWeb::new();
-                Ok(phot)
-            }
+        let (phot, _) = self.build_with_diagnostics()?;
+        Ok(phot)
+    }
+
+    /// Attempts to build the photometric web from the provided information, also returning
+    /// any non-fatal issues found while parsing the input file.
+    pub fn build_with_diagnostics(&self) -> Result<(PhotometricWeb, Diagnostics), Error> {
+        let (phot, diagnostics) = match &self.input_file {
+            Some(box_path) => self.read_file(box_path)?,
+            None => (PhotometricWeb::new(), Diagnostics::new()),
+        };
+
+        match self.intensity_scale {
+            Some(factor) => Ok((ops::scale_photometric_web(&phot, factor), diagnostics)),
+            None => Ok((phot, diagnostics)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PhotometricWebBuilder;
+    use crate::{
+        err::Error,
+        photweb::{PhotometricWeb, PhotometricWebReader, Plane},
+    };
+    use approx::assert_relative_eq;
+    use std::{error::Error as StdError, fs, path::Path};
+
+    /// Check that the intensity scale is applied to the web once it has been read.
+    #[test]
+    fn test_with_intensity_scale() {
+        let path = Path::new("./src/io/eulumdat/example.ldt");
+        let unscaled = PhotometricWebBuilder::from_file(path).build().unwrap();
+        match PhotometricWebBuilder::from_file(path)
+            .with_intensity_scale(0.5)
+            .build()
+        {
+            Ok(scaled) => assert_relative_eq!(
+                scaled.total_intensity(),
+                0.5 * unscaled.total_intensity(),
+                max_relative = 1E-12
+            ),
+            Err(e) => panic!("LDT build error: {}", e),
+        }
+    }
+
+    /// Check that uppercase file extensions resolve to the correct parser.
+    #[test]
+    fn test_get_file_parser_uppercase_extension() {
+        let dir = std::env::temp_dir().join("lidrs_test_uppercase_extension");
+        fs::create_dir_all(&dir).unwrap();
+
+        let ldt_path = dir.join("fixture.LDT");
+        fs::copy("./src/io/eulumdat/example.ldt", &ldt_path).unwrap();
+        match PhotometricWebBuilder::from_file(&ldt_path).build() {
+            Ok(photweb) => assert_eq!(photweb.n_planes(), 20),
+            Err(e) => panic!("LDT build error: {}", e),
+        }
+
+        let ies_path = dir.join("LAMP.IES");
+        fs::copy("./src/io/ies/iesna2002_example_typec.ies", &ies_path).unwrap();
+        match PhotometricWebBuilder::from_file(&ies_path).build() {
+            Ok(photweb) => assert_eq!(photweb.n_planes(), 8),
+            Err(e) => panic!("IES build error: {}", e),
+        }
+
+        // Unknown extensions are still rejected, with the original case preserved.
+        match PhotometricWebBuilder::default().get_file_parser(Path::new("lamp.TXT")) {
+            Err(Error::InvalidFileType(ext)) => assert_eq!(ext, "TXT"),
+            _ => panic!("Expected an invalid file type error."),
+        }
+    }
+
+    /// A reader for a custom format, which always returns a single plane.
+    struct DummyReader;
+
+    impl PhotometricWebReader for DummyReader {
+        fn read(&self, _path: &Path) -> Result<PhotometricWeb, Error> {
+            let mut plane = Plane::new();
+            plane.set_angles_degrees(&[0.0, 90.0, 180.0]);
+            plane.set_intensities(vec![1.0; 3]);
+            let mut web = PhotometricWeb::new();
+            web.set_planes(vec![plane]);
+            Ok(web)
+        }
+    }
+
+    /// Check that a registered format is used to read files with its extension.
+    #[test]
+    fn test_register_format() {
+        let path = Path::new("lamp.dummy");
+        assert!(matches!(
+            PhotometricWebBuilder::from_file(path).build(),
+            Err(Error::InvalidFileType(_))
+        ));
+
+        match PhotometricWebBuilder::from_file(path)
+            .register_format(".DUMMY", || Box::new(DummyReader))
+            .build()
+        {
+            Ok(photweb) => assert_eq!(photweb.n_planes(), 1),
+            Err(e) => panic!("Dummy build error: {}", e),
+        }
+    }
+
+    /// Check that a missing input file is reported as a build error, caused by the IO error for that file.
+    #[test]
+    fn test_build_missing_file() {
+        let path = Path::new("./src/io/eulumdat/missing.ldt");
+        let err = match PhotometricWebBuilder::from_file(path).build() {
+            Ok(_) => panic!("Expected a build error."),
+            Err(e) => e,
+        };
+        assert!(matches!(err, Error::BuildError(_)));
+        assert!(err.to_string().starts_with("Photometric Web Build Error: "));
+
+        let file_err = err.source().unwrap();
+        assert!(file_err.to_string().contains("missing.ldt"));
+        let io_err = file_err
+            .source()
+            .unwrap()
+            .downcast_ref::<std::io::Error>()
+            .unwrap();
+        assert_eq!(io_err.kind(), std::io::ErrorKind::NotFound);
+    }
+
+    /// Check that a gzipped copy of the sample IES file builds the same web as the uncompressed file.
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn test_build_gzipped() {
+        use flate2::{write::GzEncoder, Compression};
+        use std::io::Write;
+
+        let ies_path = Path::new("./src/io/ies/iesna2002_example_typec.ies");
+        let dir = std::env::temp_dir().join("lidrs_test_build_gzipped");
+        fs::create_dir_all(&dir).unwrap();
+        let gz_path = dir.join("example.ies.gz");
+        let mut encoder =
+            GzEncoder::new(fs::File::create(&gz_path).unwrap(), Compression::default());
+        encoder.write_all(&fs::read(ies_path).unwrap()).unwrap();
+        encoder.finish().unwrap();
+
+        let expected = PhotometricWebBuilder::from_file(ies_path).build().unwrap();
+        let web = PhotometricWebBuilder::from_file(&gz_path).build().unwrap();
+        assert!(web.approx_eq(&expected, 0.0));
+        assert_eq!(web.total_rated_lumens(), expected.total_rated_lumens());
+
+        // Readers that can only read from a path can't read compressed files.
+        let result = PhotometricWebBuilder::from_file(&gz_path)
+            .register_format("ies", || Box::new(DummyReader))
+            .build();
+        match result {
+            Err(Error::BuildError(err)) => assert!(matches!(*err, Error::IOError(_))),
+            res => panic!("Expected a build error, got {:?}", res.map(|_| ())),
         }
+        fs::remove_dir_all(&dir).unwrap();
     }
 }
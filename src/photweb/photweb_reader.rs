@@ -1,7 +1,26 @@
-use crate::{err::Error, photweb::PhotometricWeb};
+use crate::{err::Error, io::Diagnostics, photweb::PhotometricWeb};
 use std::path::Path;
 
 /// A trait that can read from a file.
 pub trait PhotometricWebReader {
     fn read(&self, path: &Path) -> Result<PhotometricWeb, Error>;
+
+    /// Reads from a file, also returning any non-fatal issues found while parsing.
+    /// By default, no diagnostics are reported.
+    fn read_with_diagnostics(&self, path: &Path) -> Result<(PhotometricWeb, Diagnostics), Error> {
+        Ok((self.read(path)?, Diagnostics::new()))
+    }
+
+    /// Reads from the contents of a file that have already been loaded, such as after decompressing it,
+    /// also returning any non-fatal issues found while parsing. Readers that can only read from a path
+    /// return an `Unsupported` IO error by default.
+    fn read_str_with_diagnostics(
+        &self,
+        _contents: &str,
+    ) -> Result<(PhotometricWeb, Diagnostics), Error> {
+        Err(Error::IOError(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "this reader can only read from a file path",
+        )))
+    }
 }
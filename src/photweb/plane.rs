@@ -1,21 +1,25 @@
-use crate::util::geom::{degrees_to_radians, radians_to_degrees};
+use crate::{
+    ops::err::Error,
+    util::geom::{degrees_to_radians, radians_to_degrees},
+};
 use property::Property;
-use std::{default::Default};
+use std::{
+    default::Default,
+    f64::consts::{FRAC_PI_2, PI},
+};
 
-use super::units::IntensityUnits;
+use super::{integration::IntegrationMethod, units::IntensityUnits};
 
-#[derive(Debug, Clone)]
+/// The tolerance (in radians) used when deciding whether a sample lies in a hemisphere.
+const HEMISPHERE_TOLERANCE: f64 = 1E-9;
+
+#[derive(Debug, Clone, Default)]
 pub enum PlaneOrientation {
+    #[default]
     Vertical,
     Horizontal,
 }
 
-impl Default for PlaneOrientation {
-    fn default() -> Self {
-        PlaneOrientation::Vertical
-    }
-}
-
 #[derive(Debug, Clone)]
 pub enum PlaneWidth {
     Symmetric(f64),
@@ -84,13 +88,38 @@ impl Plane {
         }
     }
 
+    /// Creates a plane at the given angle (in radians) from its gamma angles (in radians) and intensities,
+    /// stored in the given units. The gamma angles and intensities must be the same length, otherwise
+    /// an `InconsistentIntensitiesInPlane` error is returned.
+    pub fn from_samples(
+        angle_rad: f64,
+        gamma_rads: Vec<f64>,
+        intensities: Vec<f64>,
+        units: IntensityUnits,
+    ) -> Result<Self, Error> {
+        if gamma_rads.len() != intensities.len() {
+            return Err(Error::InconsistentIntensitiesInPlane(
+                gamma_rads.len(),
+                intensities.len(),
+            ));
+        }
+
+        Ok(Self {
+            angle: angle_rad,
+            angles: gamma_rads,
+            intensities,
+            units,
+            ..Default::default()
+        })
+    }
+
     /// Sets the angle of the plane, given in degrees.
     pub fn set_angle_degrees(&mut self, ang_deg: f64) {
         self.set_angle(degrees_to_radians(ang_deg));
     }
 
     /// Sets the angles, given in degrees.
-    pub fn set_angles_degrees(&mut self, ang_deg: &Vec<f64>) {
+    pub fn set_angles_degrees(&mut self, ang_deg: &[f64]) {
         self.set_angles(
             ang_deg
                 .iter()
@@ -101,7 +130,7 @@ impl Plane {
 
     /// Returns the number of angle / intensity pairs in the current plane object.
     pub fn n_samples(&self) -> usize {
-        self.angles.iter().count()
+        self.angles.len()
     }
 
     /// Get the angle of the plane in degrees.
@@ -117,11 +146,187 @@ impl Plane {
             .collect()
     }
 
+    /// Checks whether two planes are equal to within `epsilon`, comparing the plane angle,
+    /// and each of the gamma angles and intensities.
+    pub fn approx_eq(&self, other: &Plane, epsilon: f64) -> bool {
+        let all_close = |vals1: &[f64], vals2: &[f64]| {
+            vals1.len() == vals2.len()
+                && vals1
+                    .iter()
+                    .zip(vals2.iter())
+                    .all(|(val1, val2)| (val1 - val2).abs() <= epsilon)
+        };
+        (self.angle - other.angle).abs() <= epsilon
+            && all_close(&self.angles, &other.angles)
+            && all_close(&self.intensities, &other.intensities)
+    }
+
+    /// Returns an iterator over the (gamma angle (radians), intensity) samples in the plane.
+    pub fn iter_samples(&self) -> impl Iterator<Item = (f64, f64)> + '_ {
+        self.angles
+            .iter()
+            .zip(self.intensities.iter())
+            .map(|(angle, intensity)| (*angle, *intensity))
+    }
+
+    /// Sets any negative intensities in the plane to zero. Measured files can contain small negative
+    /// values from noise, which are not physical and upset integration.
+    pub fn clamp_negative(&mut self) {
+        self.intensities
+            .iter_mut()
+            .for_each(|intens| *intens = intens.max(0.0));
+    }
+
+    /// Sets the intensities of all samples with a gamma angle (in radians) greater than `max_gamma_rad` to zero,
+    /// such as to model an aperture that blocks the light outside of a cone around nadir.
+    pub fn apply_gamma_cutoff(&mut self, max_gamma_rad: f64) {
+        self.angles
+            .iter()
+            .zip(self.intensities.iter_mut())
+            .filter(|(gamma, _)| **gamma > max_gamma_rad)
+            .for_each(|(_, intens)| *intens = 0.0);
+    }
+
+    /// Repairs gamma angles that are not strictly increasing because of rounding, by merging each sample that is
+    /// within `tolerance` (radians) of the previous one into it, averaging their intensities. Larger inversions
+    /// are left in place, as they can't be attributed to rounding.
+    pub fn repair_monotonic(&mut self, tolerance: f64) {
+        let mut angles: Vec<f64> = Vec::with_capacity(self.angles.len());
+        let mut intensities: Vec<f64> = Vec::with_capacity(self.intensities.len());
+        let mut n_merged = 1;
+        for (gamma, intens) in self.iter_samples() {
+            match (angles.last(), intensities.last_mut()) {
+                (Some(prev), Some(prev_intens)) if (gamma - prev).abs() <= tolerance => {
+                    *prev_intens =
+                        (*prev_intens * n_merged as f64 + intens) / (n_merged + 1) as f64;
+                    n_merged += 1;
+                }
+                _ => {
+                    angles.push(gamma);
+                    intensities.push(intens);
+                    n_merged = 1;
+                }
+            }
+        }
+        self.angles = angles;
+        self.intensities = intensities;
+    }
+
+    /// Enforces symmetry about gamma = pi / 2 by averaging the intensity at each gamma angle with the intensity
+    /// at pi - gamma, interpolated using `intensity_at`. Samples whose reflection falls outside of the sampled
+    /// range are left unchanged.
+    pub fn symmetrize(&mut self) {
+        let (first, last) = match (self.angles.first(), self.angles.last()) {
+            (Some(first), Some(last)) => (*first, *last),
+            _ => return,
+        };
+        self.intensities = self
+            .iter_samples()
+            .map(|(gamma, intens)| {
+                let mirror = PI - gamma;
+                if mirror < first || mirror > last {
+                    intens
+                } else {
+                    0.5 * (intens + self.intensity_at(mirror))
+                }
+            })
+            .collect();
+    }
+
+    /// Flips the gamma convention of the plane, mapping each gamma angle to pi - gamma so that nadir and
+    /// zenith are swapped. The samples are reversed, so the gamma angles remain in ascending order.
+    pub fn flip_gamma(&mut self) {
+        self.angles = self.angles.iter().rev().map(|gamma| PI - gamma).collect();
+        self.intensities.reverse();
+    }
+
+    /// Removes the samples that lie outside of the requested hemisphere: the upper hemisphere keeps
+    /// gamma angles from 90 -> 180 degrees, and the lower hemisphere from 0 -> 90 degrees.
+    /// Samples at exactly 90 degrees are kept in both.
+    pub fn trim_to_hemisphere(&mut self, upper: bool) {
+        let in_hemisphere = |gamma: f64| {
+            if upper {
+                gamma >= FRAC_PI_2 - HEMISPHERE_TOLERANCE
+            } else {
+                gamma <= FRAC_PI_2 + HEMISPHERE_TOLERANCE
+            }
+        };
+        let (angles, intensities) = self
+            .iter_samples()
+            .filter(|(gamma, _)| in_hemisphere(*gamma))
+            .unzip();
+        self.angles = angles;
+        self.intensities = intensities;
+    }
+
+    /// Returns the intensity at the given gamma angle (in radians), interpolating linearly between samples.
+    /// Angles outside of the sampled range are clamped to the first or last sample, and a plane
+    /// with no samples returns zero.
+    pub fn intensity_at(&self, gamma_rad: f64) -> f64 {
+        let n = self.angles.len().min(self.intensities.len());
+        if n == 0 {
+            return 0.0;
+        }
+
+        let angles = &self.angles[..n];
+        let intensities = &self.intensities[..n];
+        match angles.iter().position(|ang| *ang >= gamma_rad) {
+            Some(0) => intensities[0],
+            // Return sampled angles exactly, rather than through the interpolation.
+            Some(i) if angles[i] == gamma_rad => intensities[i],
+            Some(i) => {
+                let frac = (gamma_rad - angles[i - 1]) / (angles[i] - angles[i - 1]);
+                intensities[i - 1] + frac * (intensities[i] - intensities[i - 1])
+            }
+            None => intensities[n - 1],
+        }
+    }
+
+    /// Returns a copy of the plane with `n` equally spaced gamma angles spanning the current range of
+    /// gamma angles, with the intensities interpolated at each using `intensity_at`. The other properties
+    /// of the plane, such as its angle, orientation and units, are preserved. If `n` is one, the single sample
+    /// is placed at the first gamma angle, and a plane without samples is returned unchanged.
+    pub fn resample(&self, n: usize) -> Plane {
+        let mut plane = self.clone();
+        let (first, last) = match (self.angles.first(), self.angles.last()) {
+            (Some(first), Some(last)) => (*first, *last),
+            _ => return plane,
+        };
+
+        let step = if n > 1 {
+            (last - first) / (n - 1) as f64
+        } else {
+            0.0
+        };
+        // The last angle is set directly, so that the end points are preserved exactly.
+        let angles: Vec<f64> = (0..n)
+            .map(|i| {
+                if i + 1 == n && n > 1 {
+                    last
+                } else {
+                    first + i as f64 * step
+                }
+            })
+            .collect();
+        plane.set_intensities(
+            angles
+                .iter()
+                .map(|gamma| self.intensity_at(*gamma))
+                .collect::<Vec<f64>>(),
+        );
+        plane.set_angles(angles);
+        plane
+    }
+
     /// The delta angle for a given angle in the plane - used for integration.
+    /// A plane with fewer than two samples has no extent, so 0.0 is returned.
     pub fn delta_angle(&self, i: usize) -> f64 {
+        if self.n_samples() < 2 {
+            return 0.0;
+        }
         match i {
             0 => self.angles[1] - self.angles[0],
-            x if x >= self.angles.iter().count() - 1 => self.angles[i] - self.angles[i - 1],
+            x if x >= self.angles.len() - 1 => self.angles[i] - self.angles[i - 1],
             _ => {
                 0.5 * ((self.angles[i] - self.angles[i - 1])
                     + (self.angles[i + 1] - self.angles[i]))
@@ -129,20 +334,82 @@ impl Plane {
         }
     }
 
+    /// The extent of the integration cell around a given angle in the plane, as (lower, upper) angles.
+    /// The cells have the same widths as returned by `delta_angle`, split either side of the sample.
+    fn cell_bounds(&self, i: usize) -> (f64, f64) {
+        let last = self.angles.len() - 1;
+        let lower = match i {
+            0 => 0.5 * self.delta_angle(i),
+            _ => 0.5 * (self.angles[i] - self.angles[i - 1]),
+        };
+        let upper = match i {
+            x if x >= last => 0.5 * self.delta_angle(i),
+            _ => 0.5 * (self.angles[i + 1] - self.angles[i]),
+        };
+        (self.angles[i] - lower, self.angles[i] + upper)
+    }
+
+    /// Integrate the energy being emitted by this plane between two gamma angles (in radians).
+    /// Where a boundary falls between samples, the sample is weighted by the fraction of its
+    /// integration cell that lies within the range.
+    pub fn integrate_intensity_range(&self, gamma_lower_rad: f64, gamma_upper_rad: f64) -> f64 {
+        if self.n_samples() < 2 {
+            return 0.0;
+        }
+        self.width.total()
+            * self
+                .intensities
+                .iter()
+                .enumerate()
+                .map(|(i, int)| {
+                    let (cell_lower, cell_upper) = self.cell_bounds(i);
+                    let overlap = cell_upper.min(gamma_upper_rad) - cell_lower.max(gamma_lower_rad);
+                    int * f64::sin(self.angles[i]) * overlap.max(0.0)
+                })
+                .sum::<f64>()
+    }
+
     /// Integrate the total energy being emitted by this plane.
+    /// A plane with fewer than two samples has no extent to integrate over, so 0.0 is returned.
     pub fn integrate_intensity(&self) -> f64 {
+        if self.n_samples() < 2 {
+            return 0.0;
+        }
         self.width.total() * self.intensities
             .iter()
             .enumerate()
             .map(|(i, int)| int * f64::sin(self.angles[i]) * self.delta_angle(i))
             .sum::<f64>()
     }
+
+    /// Integrate the total energy being emitted by this plane, using the given integration method.
+    /// The `Midpoint` method is the same as `integrate_intensity`.
+    /// A plane with fewer than two samples has no extent to integrate over, so 0.0 is returned.
+    pub fn integrate_intensity_with(&self, method: IntegrationMethod) -> f64 {
+        match method {
+            IntegrationMethod::Midpoint => self.integrate_intensity(),
+            IntegrationMethod::Trapezoidal => {
+                self.width.total()
+                    * self
+                        .angles
+                        .windows(2)
+                        .zip(self.intensities.windows(2))
+                        .map(|(gammas, intens)| {
+                            0.5 * (intens[0] * gammas[0].sin() + intens[1] * gammas[1].sin())
+                                * (gammas[1] - gammas[0])
+                        })
+                        .sum::<f64>()
+            }
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{Plane, PlaneWidth};
+    use super::{IntegrationMethod, IntensityUnits, Plane, PlaneWidth};
+    use crate::{ops::err::Error, util::geom::degrees_to_radians};
     use approx::assert_abs_diff_eq;
+    use std::f64::consts::PI;
 
     /// In this case, I am filling the array with a constant of 1.0, which simplifies the integral for each plane
     /// to being $\int^{\pi}_{0} \sin(\phi) d\phi$. Once fully integrated and substituted, this will result in a final
@@ -154,7 +421,6 @@ mod tests {
         plane.set_width(PlaneWidth::Symmetric(1.0));
         plane.set_angles_degrees(
             &(0..181)
-                .into_iter()
                 .map(|ang_i| ang_i as f64)
                 .collect::<Vec<f64>>(),
         );
@@ -163,4 +429,274 @@ mod tests {
         // Check that this is true to within 0.01 per cent.
         assert_abs_diff_eq!(plane.integrate_intensity(), 2.0, epsilon = 2.0E-4);
     }
+
+    /// Both integration methods should give 2.0 for a constant distribution, as in `test_integrate_plane`.
+    #[test]
+    fn test_integrate_plane_with() {
+        let mut plane = Plane::new();
+        plane.set_width(PlaneWidth::Symmetric(1.0));
+        plane.set_angles_degrees(
+            &(0..181)
+                .step_by(5)
+                .map(|ang| ang as f64)
+                .collect::<Vec<f64>>(),
+        );
+        plane.set_intensities(vec![1.0; plane.n_samples()]);
+
+        let midpoint = plane.integrate_intensity_with(IntegrationMethod::Midpoint);
+        let trapezoidal = plane.integrate_intensity_with(IntegrationMethod::Trapezoidal);
+        assert_eq!(midpoint, plane.integrate_intensity());
+        assert_abs_diff_eq!(midpoint, 2.0, epsilon = 2.0E-3);
+        assert_abs_diff_eq!(trapezoidal, 2.0, epsilon = 2.0E-3);
+
+        // Over the lower hemisphere, the midpoint cell of the last sample extends beyond 90 degrees,
+        // whereas the trapezoidal rule stops at the last sample.
+        plane.set_angles_degrees(
+            &(0..91)
+                .step_by(5)
+                .map(|ang| ang as f64)
+                .collect::<Vec<f64>>(),
+        );
+        plane.set_intensities(vec![1.0; plane.n_samples()]);
+        let half_bin = 0.5 * degrees_to_radians(5.0);
+        assert_abs_diff_eq!(
+            plane.integrate_intensity_with(IntegrationMethod::Trapezoidal),
+            1.0,
+            epsilon = 2.0E-3
+        );
+        assert_abs_diff_eq!(
+            plane.integrate_intensity_with(IntegrationMethod::Midpoint),
+            1.0 + half_bin,
+            epsilon = 2.0E-3
+        );
+
+        plane.set_angles(vec![0.0]);
+        plane.set_intensities(vec![1.0]);
+        assert_eq!(
+            plane.integrate_intensity_with(IntegrationMethod::Trapezoidal),
+            0.0
+        );
+    }
+
+    /// Check that integrating two adjacent sub-ranges gives the same result as integrating the whole plane,
+    /// including when the boundary between them falls between two samples.
+    #[test]
+    fn test_integrate_plane_range() {
+        let mut plane = Plane::new();
+        plane.set_width(PlaneWidth::Symmetric(1.0));
+        plane.set_angles_degrees(
+            &(0..181)
+                .step_by(5)
+                .map(|ang_i| ang_i as f64)
+                .collect::<Vec<f64>>(),
+        );
+        plane.set_intensities(
+            plane
+                .angles()
+                .iter()
+                .map(|ang| 1.0 + ang.cos())
+                .collect::<Vec<f64>>(),
+        );
+
+        let full = plane.integrate_intensity();
+        assert_abs_diff_eq!(plane.integrate_intensity_range(0.0, PI), full, epsilon = 1E-12);
+
+        for split_deg in [40.0, 42.5, 43.0, 90.0] {
+            let split = degrees_to_radians(split_deg);
+            let lower = plane.integrate_intensity_range(0.0, split);
+            let upper = plane.integrate_intensity_range(split, PI);
+            assert!(lower > 0.0 && upper > 0.0);
+            assert_abs_diff_eq!(lower + upper, full, epsilon = 1E-12);
+        }
+    }
+
+    /// Check that planes with too few samples to integrate don't panic, and integrate to zero.
+    #[test]
+    fn test_integrate_tiny_plane() {
+        let mut plane = Plane::new();
+        assert_eq!(plane.delta_angle(0), 0.0);
+        assert_eq!(plane.integrate_intensity(), 0.0);
+
+        plane.set_angles(vec![0.0]);
+        plane.set_intensities(vec![1.0]);
+        assert_eq!(plane.delta_angle(0), 0.0);
+        assert_eq!(plane.integrate_intensity(), 0.0);
+        assert_eq!(plane.integrate_intensity_range(0.0, PI), 0.0);
+    }
+
+    /// Check that intensities are interpolated linearly between samples, and clamped outside of the sampled range.
+    #[test]
+    fn test_intensity_at() {
+        let mut plane = Plane::new();
+        assert_eq!(plane.intensity_at(0.5), 0.0);
+
+        plane.set_angles_degrees(&[0.0, 40.0, 80.0]);
+        plane.set_intensities(vec![10.0, 20.0, 40.0]);
+        assert_abs_diff_eq!(
+            plane.intensity_at(degrees_to_radians(40.0)),
+            20.0,
+            epsilon = 1E-12
+        );
+        assert_abs_diff_eq!(
+            plane.intensity_at(degrees_to_radians(60.0)),
+            30.0,
+            epsilon = 1E-12
+        );
+
+        // Angles outside of the sampled range are clamped.
+        assert_abs_diff_eq!(plane.intensity_at(-1.0), 10.0, epsilon = 1E-12);
+        assert_abs_diff_eq!(plane.intensity_at(PI), 40.0, epsilon = 1E-12);
+    }
+
+    /// Check that negative intensities are clamped to zero, leaving the others untouched.
+    #[test]
+    fn test_clamp_negative() {
+        let mut plane = Plane::new();
+        plane.set_angles_degrees(&[0.0, 45.0, 90.0, 135.0, 180.0]);
+        plane.set_intensities(vec![10.0, -0.5, 5.0, -1E-6, 0.0]);
+        plane.clamp_negative();
+        assert_eq!(plane.intensities(), &[10.0, 0.0, 5.0, 0.0, 0.0]);
+    }
+
+    /// Check that the intensities beyond the cutoff angle are zeroed, leaving those within it untouched.
+    #[test]
+    fn test_apply_gamma_cutoff() {
+        let mut plane = Plane::new();
+        plane.set_angles_degrees(&[0.0, 15.0, 30.0, 45.0, 90.0]);
+        plane.set_intensities(vec![10.0, 8.0, 6.0, 4.0, 2.0]);
+        plane.apply_gamma_cutoff(PI / 6.0 + 1E-9);
+        assert_eq!(plane.intensities(), &[10.0, 8.0, 6.0, 0.0, 0.0]);
+    }
+
+    /// A near-duplicate gamma angle from rounding should be merged, giving the same integral as the clean plane.
+    #[test]
+    fn test_repair_monotonic() {
+        let mut clean = Plane::new();
+        clean.set_angles_degrees(&[0.0, 45.0, 90.0, 135.0, 180.0]);
+        clean.set_intensities(vec![1.0, 2.0, 3.0, 2.0, 1.0]);
+
+        // Duplicate the 90 degree sample, with a tiny inversion from rounding.
+        let mut plane = Plane::new();
+        let mut angles = clean.angles().to_vec();
+        angles.insert(3, angles[2] - 1E-12);
+        plane.set_angles(angles);
+        plane.set_intensities(vec![1.0, 2.0, 3.0, 3.0, 2.0, 1.0]);
+        assert!(!plane.angles().windows(2).all(|pair| pair[1] > pair[0]));
+
+        plane.repair_monotonic(1E-9);
+        assert_eq!(plane.n_samples(), 5);
+        assert_eq!(plane.intensities(), clean.intensities());
+        assert!(plane.angles().windows(2).all(|pair| pair[1] > pair[0]));
+        assert!((0..plane.n_samples()).all(|i| plane.delta_angle(i) > 0.0));
+        assert_abs_diff_eq!(
+            plane.integrate_intensity(),
+            clean.integrate_intensity(),
+            epsilon = 1E-9
+        );
+    }
+
+    /// Check that flipping the plane maps each gamma angle to 180 degrees minus itself, reversing the samples.
+    #[test]
+    fn test_flip_gamma() {
+        let mut plane = Plane::new();
+        plane.set_angles_degrees(&[0.0, 30.0, 90.0, 180.0]);
+        plane.set_intensities(vec![10.0, 8.0, 4.0, 1.0]);
+        plane.flip_gamma();
+        let angles = plane.angles_deg();
+        for (angle, expected) in angles.iter().zip([0.0, 90.0, 150.0, 180.0]) {
+            assert_abs_diff_eq!(*angle, expected, epsilon = 1E-9);
+        }
+        assert_eq!(plane.intensities(), &vec![1.0, 4.0, 8.0, 10.0]);
+    }
+
+    /// Check that trimming keeps the samples in the requested hemisphere, including the horizontal.
+    #[test]
+    fn test_trim_to_hemisphere() {
+        let mut plane = Plane::new();
+        plane.set_angles_degrees(&[0.0, 45.0, 90.0, 135.0, 180.0]);
+        plane.set_intensities(vec![1.0, 2.0, 3.0, 4.0, 5.0]);
+
+        let mut lower = plane.clone();
+        lower.trim_to_hemisphere(false);
+        assert_eq!(lower.n_samples(), 3);
+        assert_eq!(lower.intensities(), &[1.0, 2.0, 3.0]);
+
+        plane.trim_to_hemisphere(true);
+        assert_eq!(plane.n_samples(), 3);
+        assert_eq!(plane.intensities(), &[3.0, 4.0, 5.0]);
+    }
+
+    /// Check that resampling gives equally spaced samples, keeping the end points and interpolating between them.
+    #[test]
+    fn test_resample() {
+        let mut plane = Plane::new();
+        plane.set_angle_degrees(90.0);
+        plane.set_angles_degrees(&[0.0, 45.0, 90.0, 135.0, 180.0]);
+        plane.set_intensities(vec![10.0, 8.0, 6.0, 4.0, 2.0]);
+
+        let resampled = plane.resample(181);
+        assert_eq!(resampled.n_samples(), 181);
+        assert_eq!(resampled.intensities().len(), 181);
+        assert_eq!(resampled.angle(), plane.angle());
+        assert_eq!(resampled.angles()[0], plane.angles()[0]);
+        assert_eq!(resampled.angles()[180], plane.angles()[4]);
+        assert_eq!(resampled.intensities()[0], 10.0);
+        assert_eq!(resampled.intensities()[180], 2.0);
+
+        // The samples are one degree apart, and interpolated between the original samples.
+        assert_abs_diff_eq!(
+            resampled.angles()[1],
+            degrees_to_radians(1.0),
+            epsilon = 1E-12
+        );
+        assert_abs_diff_eq!(
+            resampled.intensities()[30],
+            10.0 - 2.0 * 30.0 / 45.0,
+            epsilon = 1E-9
+        );
+    }
+
+    /// Check that a plane is built from matching samples, and that mismatched lengths are an error.
+    #[test]
+    fn test_from_samples() {
+        let gammas = vec![0.0, PI / 2.0, PI];
+        match Plane::from_samples(
+            PI / 4.0,
+            gammas.clone(),
+            vec![3.0, 2.0, 1.0],
+            IntensityUnits::Candela,
+        ) {
+            Ok(plane) => {
+                assert_eq!(plane.angle(), PI / 4.0);
+                assert_eq!(plane.angles(), &gammas);
+                assert_eq!(plane.intensities(), &vec![3.0, 2.0, 1.0]);
+                assert!(matches!(plane.units(), IntensityUnits::Candela));
+                assert_eq!(plane.n_samples(), 3);
+            }
+            Err(e) => panic!("Plane error: {}", e),
+        }
+
+        assert!(matches!(
+            Plane::from_samples(0.0, gammas, vec![1.0], IntensityUnits::Candela),
+            Err(Error::InconsistentIntensitiesInPlane(3, 1))
+        ));
+    }
+
+    /// A plane that is nearly symmetric about the horizontal should become exactly symmetric, and the
+    /// samples without a reflection in the range should be kept.
+    #[test]
+    fn test_symmetrize() {
+        let mut plane = Plane::new();
+        plane.set_angles_degrees(&[0.0, 45.0, 90.0, 135.0, 180.0]);
+        plane.set_intensities(vec![10.0, 6.0, 4.0, 8.0, 12.0]);
+        plane.symmetrize();
+        assert_eq!(plane.intensities(), &vec![11.0, 7.0, 4.0, 7.0, 11.0]);
+
+        let mut partial = Plane::new();
+        partial.set_angles_degrees(&[0.0, 90.0, 120.0]);
+        partial.set_intensities(vec![10.0, 4.0, 2.0]);
+        partial.symmetrize();
+        assert_eq!(partial.intensities()[0], 10.0);
+        assert_eq!(partial.intensities()[1], 4.0);
+    }
 }
@@ -0,0 +1,51 @@
+use property::Property;
+
+/// A summary of the commonly reported photometric metrics of a distribution,
+/// as returned by `PhotometricWeb::photometric_report`.
+#[derive(Debug, Clone, PartialEq, Property)]
+#[property(get(public), set(disable))]
+pub struct PhotometricReport {
+    /// The total flux emitted by the luminaire.
+    total_flux: f64,
+    /// The light output ratio (LOR), relative to the rated lumens the report was generated with.
+    /// This is `None` if the rated lumens were not positive.
+    light_output_ratio: Option<f64>,
+    /// The downward flux fraction (DFF): the fraction of the flux emitted into the lower hemisphere.
+    downward_flux_fraction: f64,
+    /// The flux emitted into the lower hemisphere (gamma 0 -> 90 degrees).
+    downward_flux: f64,
+    /// The flux emitted into the upper hemisphere (gamma 90 -> 180 degrees).
+    upward_flux: f64,
+    /// The peak intensity of the distribution.
+    peak_intensity: f64,
+    /// The direction of the peak intensity, as (C-angle, gamma) in radians.
+    peak_direction: (f64, f64),
+    /// The beam angle (in radians), as returned by `PhotometricWeb::beam_angle`.
+    beam_angle: f64,
+}
+
+impl PhotometricReport {
+    /// Returns a new report from the individual metrics.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        total_flux: f64,
+        light_output_ratio: Option<f64>,
+        downward_flux_fraction: f64,
+        downward_flux: f64,
+        upward_flux: f64,
+        peak_intensity: f64,
+        peak_direction: (f64, f64),
+        beam_angle: f64,
+    ) -> Self {
+        Self {
+            total_flux,
+            light_output_ratio,
+            downward_flux_fraction,
+            downward_flux,
+            upward_flux,
+            peak_intensity,
+            peak_direction,
+            beam_angle,
+        }
+    }
+}
@@ -1,10 +1,7 @@
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, PartialEq)]
 pub enum IntensityUnits {
+    #[default]
     Candela,
-}
-
-impl Default for IntensityUnits {
-    fn default() -> Self {
-        IntensityUnits::Candela
-    }
+    /// Relative intensities, normalised to a lamp flux of 1000 lumens, as used by EULUMDAT files.
+    CandelaPerKilolumen,
 }
@@ -14,18 +14,32 @@ pub fn radians_to_degrees(radian: f64) -> f64 {
     radian * (180.0 / PI)
 }
 
+#[inline]
+#[must_use]
+/// Rounds an angle to the given number of decimal places, removing the floating point error
+/// that builds up when converting between degrees and radians.
+pub fn round_angle(angle: f64, decimals: i32) -> f64 {
+    let scale = 10_f64.powi(decimals);
+    (angle * scale).round() / scale
+}
+
 #[inline]
 #[must_use]
 /// Gets the difference (in radians) between two angles.
+/// Identical angles return exactly zero, and the argument to `acos` is clamped to [-1, 1] so that
+/// rounding error can't produce a NaN.
 pub fn angle_difference(angle1: f64, angle2:f64) -> f64 {
+    if angle1 == angle2 {
+        return 0.0;
+    }
     let dot_prod = angle1.cos().mul_add(angle2.cos(), angle1.sin() * angle2.sin());
     let mag = (angle1.cos().powi(2) + angle1.sin().powi(2)).sqrt() * (angle2.cos().powi(2) + angle2.sin().powi(2)).sqrt();
-    (dot_prod / mag).acos()
+    (dot_prod / mag).clamp(-1.0, 1.0).acos()
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{degrees_to_radians, radians_to_degrees, angle_difference};
+    use super::{degrees_to_radians, radians_to_degrees, angle_difference, round_angle};
     use std::f64::consts::PI;
     use approx::assert_abs_diff_eq;
 
@@ -45,6 +59,13 @@ mod tests {
         assert_eq!(radians_to_degrees(2.0 * PI), 360.0);
     }
 
+    #[test]
+    fn test_round_angle() {
+        assert_eq!(round_angle(radians_to_degrees(degrees_to_radians(15.0)), 6), 15.0);
+        assert_eq!(round_angle(12.3456789, 2), 12.35);
+        assert_eq!(round_angle(-0.0000001, 6), 0.0);
+    }
+
     #[test]
     fn test_angle_difference() {
         assert_abs_diff_eq!(angle_difference(0., PI), PI, epsilon = 1E-6);
@@ -54,4 +75,12 @@ mod tests {
         assert_abs_diff_eq!(angle_difference(degrees_to_radians(350.), degrees_to_radians(10.)), degrees_to_radians(20.), epsilon = 1E-6);
         assert_abs_diff_eq!(angle_difference(degrees_to_radians(10.), degrees_to_radians(350.)), degrees_to_radians(20.), epsilon = 1E-6);
     }
+
+    #[test]
+    fn test_angle_difference_identical() {
+        for angle in [0.0, 0.1, 1.0, PI / 2.0, PI - 1E-12, PI, PI + 1E-12, 2.0 * PI, degrees_to_radians(350.)] {
+            assert_eq!(angle_difference(angle, angle), 0.0);
+            assert!(!angle_difference(angle, angle + 1E-9).is_nan());
+        }
+    }
 }